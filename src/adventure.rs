@@ -1,4 +1,7 @@
-use crate::{dungeon::*, encounter::*, loot::*, maze::*, player::Player, resource::Mana, spell::*};
+use crate::{
+    clock::*, consumable::Setting, dungeon::*, encounter::*, maze::*, player::Player,
+    resource::Mana, spell::*,
+};
 use regex::Regex;
 use std::{
     fmt,
@@ -16,6 +19,8 @@ pub enum AdventureAction {
     Cast,
     Equipment,
     Stats,
+    Skills,
+    Journal,
 }
 use AdventureAction::*;
 
@@ -28,6 +33,8 @@ impl AdventureAction {
             Cast => "Cast a spell",
             Equipment => "Open equipment",
             Stats => "Display character statistics",
+            Skills => "Browse and unlock passive skills",
+            Journal => "Review your active quests",
         }
     }
     pub(crate) fn print_menu_item(&self) {
@@ -48,6 +55,8 @@ impl fmt::Display for AdventureAction {
             Cast => write!(f, "{}ast", "C".bold().underline()),
             Equipment => write!(f, "{}quipment", "E".bold().underline()),
             Stats => write!(f, "{}s", "Stat".bold().underline()),
+            Skills => write!(f, "{}s", "Skill".bold().underline()),
+            Journal => write!(f, "{}ournal", "J".bold().underline()),
         }
     }
 }
@@ -67,6 +76,10 @@ impl FromStr for AdventureAction {
             LazyLock::new(|| Regex::new("(?i)^(?:equipment|e)$").unwrap());
         static RE_STATS: LazyLock<Regex> =
             LazyLock::new(|| Regex::new("(?i)^(?:stats?)$").unwrap());
+        static RE_SKILLS: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:skills?)$").unwrap());
+        static RE_JOURNAL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:journal|j)$").unwrap());
 
         if RE_MOVE.is_match(s) {
             Ok(Movement)
@@ -80,6 +93,10 @@ impl FromStr for AdventureAction {
             Ok(Equipment)
         } else if RE_STATS.is_match(s) {
             Ok(Stats)
+        } else if RE_SKILLS.is_match(s) {
+            Ok(Skills)
+        } else if RE_JOURNAL.is_match(s) {
+            Ok(Journal)
         } else {
             Err(s.to_string())
         }
@@ -95,6 +112,8 @@ pub fn adventure_menu() -> AdventureAction {
     Cast.print_menu_item();
     Equipment.print_menu_item();
     Stats.print_menu_item();
+    Skills.print_menu_item();
+    Journal.print_menu_item();
     loop {
         String::clear(&mut buf);
         print!("🍁 ");
@@ -111,26 +130,38 @@ pub fn adventure_menu() -> AdventureAction {
 
         let s = buf.trim();
         if let Ok(action) = s.parse::<AdventureAction>() {
-            let _ = crate::readline::clear_last_n_lines(7);
+            let _ = crate::readline::clear_last_n_lines(9);
             return action;
         }
     }
 }
 
-pub struct Adventure<'a, 'b> {
+pub struct Adventure<'a, 'b, 'c> {
     player: &'a mut Player,
     graph: &'b mut MazeGraph,
+    clock: &'c mut Clock,
     node: usize,
 }
 
-impl<'a, 'b> Adventure<'a, 'b> {
-    pub fn new(player: &'a mut Player, graph: &'b mut MazeGraph) -> Self {
+impl<'a, 'b, 'c> Adventure<'a, 'b, 'c> {
+    pub fn new(player: &'a mut Player, graph: &'b mut MazeGraph, clock: &'c mut Clock) -> Self {
         Self {
             player,
             graph,
+            clock,
             node: 0,
         }
     }
+    fn leave_corpse(&mut self) {
+        let items = self.player.drop_items();
+        if !items.is_empty() {
+            if self.graph.0[self.node].place_corpse(items) {
+                println!("Your belongings lie beside your corpse.");
+            } else {
+                println!("Your belongings are lost!");
+            }
+        }
+    }
     pub fn run(&mut self) {
         let mut should_move = false;
         'outer: loop {
@@ -139,30 +170,56 @@ impl<'a, 'b> Adventure<'a, 'b> {
                     match self.graph.0[self.node].action() {
                         MazeEvent::Interact(Element::Monster(kind), monster_pos) => {
                             let mut enc = Encounter::new(kind, &mut self.player);
+                            if !enc.scout() {
+                                continue;
+                            }
                             match enc.run() {
                                 PlayerVictory => {
                                     self.graph.0[self.node].remove_monster(monster_pos);
                                 }
-                                MonsterVictory => break 'outer,
+                                MonsterVictory => {
+                                    self.leave_corpse();
+                                    break 'outer;
+                                }
                                 _ => (),
                             }
                         }
                         MazeEvent::Interact(Element::Treasure, pos) => {
-                            let loot = Loot::rand();
-                            loot.announce();
-                            self.player.acquire(loot);
-                            self.graph.0[self.node].grid[pos] = Element::Empty;
+                            if let Some(loot) = self.graph.0[self.node].take_treasure(pos) {
+                                loot.announce();
+                                if let Some(rest) = self.player.pick_up(loot) {
+                                    self.graph.0[self.node].place_treasure(pos, rest);
+                                }
+                            }
                         }
                         MazeEvent::Interact(Element::Dungeon, _) => {
                             let mut dungeon = Dungeon::new(&mut self.player, 5);
                             dungeon.run();
+                            self.clock.advance(DUNGEON_MINUTES);
                             if !self.player.is_alive() {
+                                self.leave_corpse();
                                 break 'outer;
                             }
                         }
+                        MazeEvent::Interact(Element::Corpse, pos) => {
+                            if let Some(items) = self.graph.0[self.node].loot_corpse(pos) {
+                                println!("You recover your belongings!");
+                                self.player.inventory.append(items);
+                            }
+                        }
+                        MazeEvent::Interact(Element::Npc, pos) => {
+                            if let Some(dialogue) = self.graph.0[self.node].npcs.get(&pos).cloned()
+                            {
+                                dialogue.run(self.player, self.graph);
+                            }
+                        }
                         MazeEvent::Quit => break,
                         MazeEvent::Movement => {
-                            if should_move {
+                            self.player.step();
+                            self.clock.advance(STEP_MINUTES);
+                            // Overloaded players are as slow as the monsters,
+                            // and monsters prowl faster at night.
+                            if should_move || self.player.is_overloaded() || self.clock.is_night() {
                                 self.graph.0[self.node].monster_movement();
                                 should_move = false;
                             } else {
@@ -177,6 +234,7 @@ impl<'a, 'b> Adventure<'a, 'b> {
                             self.graph.0[self.node].player = dst.position;
                             self.graph.0[self.node].reconcile_monster_positions();
                             self.graph.0[self.node].show_player_mark();
+                            self.player.journal.explore(self.node);
                         }
                         _ => (),
                     }
@@ -184,13 +242,24 @@ impl<'a, 'b> Adventure<'a, 'b> {
                 AdventureAction::Town => {
                     break;
                 }
-                AdventureAction::Inventory => self.player.noncombat_inventory(),
+                AdventureAction::Inventory => {
+                    if self.player.noncombat_inventory(Setting::Map) {
+                        println!("You are warped back to town!");
+                        break;
+                    }
+                }
                 AdventureAction::Equipment => self.player.noncombat_equipment(),
-                AdventureAction::Stats => {
-                    println!("{}", self.player.attribute_message())
+                AdventureAction::Stats => self.player.view_stats(),
+                AdventureAction::Skills => self.player.noncombat_skills(),
+                AdventureAction::Journal => {
+                    print!("{}", self.player.journal.message(self.player));
                 }
                 AdventureAction::Cast => {
-                    if let Some(spell) = spell_menu(self.player.intellect()) {
+                    if let Some(spell) = spell_menu(
+                        self.player.intellect(),
+                        self.player.class(),
+                        &self.player.skills,
+                    ) {
                         match self.player.cast_spell(spell) {
                             Some(SpellCast::Offense(_)) => {
                                 println!("There is no target!")
@@ -1,6 +1,221 @@
-#[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq)]
+use crate::utils::is_quit;
+use regex::Regex;
+use std::{
+    fmt,
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+/// Attribute points granted at each level-up.
+pub(crate) const POINTS_PER_LEVEL: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Attribute {
+    Strength,
+    Intellect,
+    Vitality,
+    Wisdom,
+    Agility,
+    Luck,
+}
+pub use Attribute::*;
+
+impl Attribute {
+    pub const fn abbreviation(&self) -> &'static str {
+        match self {
+            Strength => "STR",
+            Intellect => "INT",
+            Vitality => "VIT",
+            Wisdom => "WIS",
+            Agility => "AGI",
+            Luck => "LCK",
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Strength => "melee damage",
+            Intellect => "spell damage and healing",
+            Vitality => "maximum HP",
+            Wisdom => "maximum MP and MP regeneration",
+            Agility => "chance to evade attacks",
+            Luck => "critical hits and loot",
+        }
+    }
+    pub(crate) const fn all() -> [Attribute; 6] {
+        [Strength, Intellect, Vitality, Wisdom, Agility, Luck]
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.abbreviation().bold().underline())
+    }
+}
+
+impl FromStr for Attribute {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        static RE_STR: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:strength|str)$").unwrap());
+        static RE_INT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:intellect|int)$").unwrap());
+        static RE_VIT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:vitality|vit)$").unwrap());
+        static RE_WIS: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:wisdom|wis)$").unwrap());
+        static RE_AGI: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:agility|agi)$").unwrap());
+        static RE_LCK: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:luck|lck)$").unwrap());
+
+        if RE_STR.is_match(s) {
+            Ok(Strength)
+        } else if RE_INT.is_match(s) {
+            Ok(Intellect)
+        } else if RE_VIT.is_match(s) {
+            Ok(Vitality)
+        } else if RE_WIS.is_match(s) {
+            Ok(Wisdom)
+        } else if RE_AGI.is_match(s) {
+            Ok(Agility)
+        } else if RE_LCK.is_match(s) {
+            Ok(Luck)
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Attributes {
     pub(crate) strength: i64,
     pub(crate) intellect: i64,
+    pub(crate) vitality: i64,
+    pub(crate) wisdom: i64,
+    pub(crate) agility: i64,
+    pub(crate) luck: i64,
+}
+
+impl Attributes {
+    pub fn new(strength: i64, intellect: i64) -> Self {
+        Self {
+            strength,
+            intellect,
+            ..Self::default()
+        }
+    }
+    pub fn get(&self, attr: Attribute) -> i64 {
+        match attr {
+            Strength => self.strength,
+            Intellect => self.intellect,
+            Vitality => self.vitality,
+            Wisdom => self.wisdom,
+            Agility => self.agility,
+            Luck => self.luck,
+        }
+    }
+    pub fn add(&mut self, attr: Attribute, amount: i64) {
+        match attr {
+            Strength => self.strength += amount,
+            Intellect => self.intellect += amount,
+            Vitality => self.vitality += amount,
+            Wisdom => self.wisdom += amount,
+            Agility => self.agility += amount,
+            Luck => self.luck += amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocationTransaction {
+    Spend { attr: Attribute, count: usize },
+    Quit,
+}
+
+impl FromStr for AllocationTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(attr) = s.parse::<Attribute>() {
+            return Ok(Self::Spend { attr, count: 1 });
+        } else if let Some((lhs, rhs)) = s.split_once(' ') {
+            if let Ok(count) = lhs.parse::<usize>() {
+                if let Ok(attr) = rhs.parse::<Attribute>() {
+                    return Ok(Self::Spend { attr, count });
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(Self::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+pub(crate) fn allocation_menu(attributes: &Attributes, points: usize) -> AllocationTransaction {
+    const N: usize = 8;
+    let mut buf = String::with_capacity(1 << 7);
+    println!(
+        "---- Spend your attribute points ({} available)... ----",
+        points.bold()
+    );
+    for attr in Attribute::all() {
+        println!(
+            "    {}: {:<5} | {:<30}",
+            attr,
+            attributes.get(attr),
+            attr.description()
+        );
+    }
+    println!("e.g. `2 str` or `vit`; `q` to save the rest for later");
+    loop {
+        String::clear(&mut buf);
+        print!("✨ ");
+        io::Write::flush(&mut io::stdout()).unwrap();
+
+        let stdin = io::stdin();
+        let mut handle = stdin.lock();
+        match handle.read_line(&mut buf) {
+            Ok(_) => {
+                let _ = crate::readline::clear_last_n_lines(1);
+            }
+            Err(e) => println!("Error in attribute menu readline: {:#?}", e),
+        }
+        if let Ok(transaction) = buf.parse::<AllocationTransaction>() {
+            let _ = crate::readline::clear_last_n_lines(N);
+            break transaction;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "str".parse::<AllocationTransaction>().unwrap(),
+            AllocationTransaction::Spend {
+                attr: Strength,
+                count: 1
+            }
+        );
+        assert_eq!(
+            "3 Vitality".parse::<AllocationTransaction>().unwrap(),
+            AllocationTransaction::Spend {
+                attr: Vitality,
+                count: 3
+            }
+        );
+        assert_eq!(
+            "q".parse::<AllocationTransaction>().unwrap(),
+            AllocationTransaction::Quit
+        );
+        assert!("3".parse::<AllocationTransaction>().is_err());
+        assert!("lu".parse::<AllocationTransaction>().is_err());
+    }
 }
@@ -0,0 +1,307 @@
+use crate::{
+    inventory::Inventory,
+    item::{equipment_bag::EquipmentBag, Item},
+    player::Player,
+    trade::Quantity,
+    utils::is_quit,
+};
+use regex::Regex;
+use std::{
+    fmt::{self, Write as _},
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+/// Interest, in percent, paid on savings each day.
+pub const INTEREST_PCT: usize = 2;
+/// The most interest paid in a single day.
+pub const MAX_INTEREST: usize = 50;
+
+/// Gold and items kept safe in town, out of reach of death penalties.
+///
+/// The game has no save file yet, so the bank lasts only as long as the
+/// current run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bank {
+    pub(crate) gold: usize,
+    pub(crate) inventory: Inventory,
+    pub(crate) equipment_bag: EquipmentBag,
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        Self {
+            gold: 0,
+            inventory: Inventory::new(),
+            equipment_bag: EquipmentBag::new(),
+        }
+    }
+    /// A day passes; returns the interest earned.
+    pub fn accrue_interest(&mut self) -> usize {
+        let interest = (self.gold * INTEREST_PCT / 100).min(MAX_INTEREST);
+        self.gold += interest;
+        interest
+    }
+    pub fn n_available(&self, item: &Item) -> usize {
+        match item {
+            Item::Consumable(x) => self.inventory.n_available(x),
+            Item::Gear(x) => self.equipment_bag.n_available(x),
+        }
+    }
+}
+
+impl Default for Bank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Bank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} ({}% interest per day, up to {})",
+            "Savings".bold().underline(),
+            self.gold,
+            INTEREST_PCT,
+            MAX_INTEREST
+        )?;
+        writeln!(f, "{}:", "Stash".bold().underline())?;
+        writeln!(f, "{}", self.inventory)?;
+        write!(f, "{}", self.equipment_bag)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BankTransaction {
+    Deposit(Quantity),
+    Withdraw(Quantity),
+    Store(Quantity, Item),
+    Take(Quantity, Item),
+    Quit,
+}
+
+impl FromStr for BankTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_DEPOSIT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:deposit|d)$").unwrap());
+        static RE_WITHDRAW: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:withdraw|w)$").unwrap());
+        static RE_STORE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:store|s)$").unwrap());
+        static RE_TAKE: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:take|t)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.split_once(' ') {
+            let rhs = rhs.trim();
+            if RE_DEPOSIT.is_match(lhs) {
+                if let Ok(quantity) = rhs.parse::<Quantity>() {
+                    return Ok(Self::Deposit(quantity));
+                }
+            } else if RE_WITHDRAW.is_match(lhs) {
+                if let Ok(quantity) = rhs.parse::<Quantity>() {
+                    return Ok(Self::Withdraw(quantity));
+                }
+            } else if RE_STORE.is_match(lhs) || RE_TAKE.is_match(lhs) {
+                let (quantity, item) = match rhs.split_once(' ') {
+                    Some((n, item)) if n.parse::<Quantity>().is_ok() => {
+                        (n.parse::<Quantity>().unwrap(), item)
+                    }
+                    _ => (Quantity::Count(1), rhs),
+                };
+                if let Ok(item) = item.parse::<Item>() {
+                    if RE_STORE.is_match(lhs) {
+                        return Ok(Self::Store(quantity, item));
+                    } else {
+                        return Ok(Self::Take(quantity, item));
+                    }
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(Self::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+/// `all` and `max` both stand for everything available.
+fn count(quantity: Quantity, available: usize) -> usize {
+    match quantity {
+        Quantity::Count(n) => n,
+        Quantity::All | Quantity::Max => available,
+    }
+}
+
+/// Keeps the player's savings and stash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Banker;
+
+impl Banker {
+    pub fn message(&self, player: &Player) -> String {
+        let mut s = String::with_capacity(1 << 10);
+        write!(s, "{}", player.bank).unwrap();
+        writeln!(s, "{}: {}", "Gold".bold().underline(), player.gold).unwrap();
+        s
+    }
+    pub fn menu(&self, gold: usize, msg: &str) -> BankTransaction {
+        let mut buf = String::with_capacity(1 << 7);
+        println!("---- Visiting the bank... ----");
+        println!("{}", msg);
+        println!("e.g. `deposit 50`, `withdraw all`, `store 2 hp`, `take axe`; `q` to leave");
+        let n = msg.lines().count() + 3;
+        loop {
+            String::clear(&mut buf);
+            print!("(💰: {}) 🏦 ", gold);
+            io::Write::flush(&mut io::stdout()).unwrap();
+
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            match handle.read_line(&mut buf) {
+                Ok(_) => {
+                    let _ = crate::readline::clear_last_n_lines(1);
+                }
+                Err(e) => println!("Error in bank menu readline: {:#?}", e),
+            }
+            if let Ok(transaction) = buf.parse::<BankTransaction>() {
+                let _ = crate::readline::clear_last_n_lines(n);
+                break transaction;
+            }
+        }
+    }
+    /// Returns `false` if the transaction is rejected.
+    pub fn perform(&self, player: &mut Player, transaction: BankTransaction) -> bool {
+        match transaction {
+            BankTransaction::Deposit(quantity) => {
+                let n = count(quantity, player.gold);
+                if n > player.gold {
+                    println!("You do not have {n} gold!");
+                    return false;
+                }
+                player.gold -= n;
+                player.bank.gold += n;
+                println!("You deposited {n} gold.");
+            }
+            BankTransaction::Withdraw(quantity) => {
+                let n = count(quantity, player.bank.gold);
+                if n > player.bank.gold {
+                    println!("You do not have {n} gold in the bank!");
+                    return false;
+                }
+                player.bank.gold -= n;
+                player.gold += n;
+                println!("You withdrew {n} gold.");
+            }
+            BankTransaction::Store(quantity, item) => {
+                let n = count(quantity, player.n_available(&item));
+                if n == 0 || n > player.n_available(&item) {
+                    println!("You do not have {n} {item}!");
+                    return false;
+                }
+                match item {
+                    Item::Consumable(x) => {
+                        player.inventory.drop_multiple(x, n);
+                        player.bank.inventory.push_multiple(x, n);
+                    }
+                    Item::Gear(x) => {
+                        for piece in player.equipment_bag.pop_multiple(x, n) {
+                            player.bank.equipment_bag.push(piece);
+                        }
+                    }
+                }
+                println!("You stored {n} {item}.");
+            }
+            BankTransaction::Take(quantity, item) => {
+                let n = count(quantity, player.bank.n_available(&item));
+                if n == 0 || n > player.bank.n_available(&item) {
+                    println!("You have not stored {n} {item}!");
+                    return false;
+                }
+                match item {
+                    Item::Consumable(x) => {
+                        player.bank.inventory.drop_multiple(x, n);
+                        player.inventory.push_multiple(x, n);
+                    }
+                    Item::Gear(x) => {
+                        for piece in player.bank.equipment_bag.pop_multiple(x, n) {
+                            player.equipment_bag.push(piece);
+                        }
+                    }
+                }
+                println!("You took {n} {item}.");
+            }
+            BankTransaction::Quit => (),
+        }
+        true
+    }
+    pub fn visit(&self, player: &mut Player) -> bool {
+        match self.menu(player.gold, &self.message(player)) {
+            BankTransaction::Quit => false,
+            transaction => {
+                self.perform(player, transaction);
+                true
+            }
+        }
+    }
+    pub fn work(&self, player: &mut Player) {
+        while self.visit(player) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{consumable::HealthPotion, equipment::Gear::Axe};
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "deposit 50".parse::<BankTransaction>().unwrap(),
+            BankTransaction::Deposit(Quantity::Count(50))
+        );
+        assert_eq!(
+            "w all".parse::<BankTransaction>().unwrap(),
+            BankTransaction::Withdraw(Quantity::All)
+        );
+        assert_eq!(
+            "store 2 health potion".parse::<BankTransaction>().unwrap(),
+            BankTransaction::Store(Quantity::Count(2), Item::from(HealthPotion))
+        );
+        assert_eq!(
+            "take axe".parse::<BankTransaction>().unwrap(),
+            BankTransaction::Take(Quantity::Count(1), Item::from(Axe))
+        );
+        assert!("deposit".parse::<BankTransaction>().is_err());
+    }
+
+    #[test]
+    fn safekeeping() {
+        let mut player = Player::new(crate::class::Class::Warrior);
+        player.gold = 100;
+        player.inventory.push_multiple(HealthPotion, 2);
+        let potions = player.inventory.n_available(&HealthPotion);
+
+        assert!(Banker.perform(&mut player, BankTransaction::Deposit(Quantity::All)));
+        assert!(Banker.perform(
+            &mut player,
+            BankTransaction::Store(Quantity::All, Item::from(HealthPotion))
+        ));
+        assert!(!Banker.perform(&mut player, BankTransaction::Withdraw(Quantity::Count(101))));
+        player.apply_death_penalty();
+        assert_eq!(player.drop_items().n_available(&HealthPotion), 0);
+
+        assert_eq!(player.bank.accrue_interest(), 100 * INTEREST_PCT / 100);
+        assert!(Banker.perform(&mut player, BankTransaction::Withdraw(Quantity::All)));
+        assert_eq!(player.gold, 100 + 100 * INTEREST_PCT / 100);
+        assert!(Banker.perform(
+            &mut player,
+            BankTransaction::Take(Quantity::Count(potions), Item::from(HealthPotion))
+        ));
+        assert_eq!(player.inventory.n_available(&HealthPotion), potions);
+
+        player.bank.gold = usize::MAX / 100;
+        assert_eq!(player.bank.accrue_interest(), MAX_INTEREST);
+    }
+}
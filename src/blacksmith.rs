@@ -0,0 +1,122 @@
+use crate::{equipment::Gear, player::Player, utils::is_quit};
+use regex::Regex;
+use std::{
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairTransaction {
+    Repair(Gear),
+    RepairAll,
+    Quit,
+}
+
+impl RepairTransaction {
+    fn kind(&self) -> Option<Gear> {
+        match self {
+            Self::Repair(kind) => Some(*kind),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for RepairTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_REPAIR: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:repair|r)$").unwrap());
+        static RE_ALL: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:all|a)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.split_once(' ') {
+            if RE_REPAIR.is_match(lhs) {
+                let rhs = rhs.trim();
+                if RE_ALL.is_match(rhs) {
+                    return Ok(Self::RepairAll);
+                } else if let Ok(kind) = rhs.parse::<Gear>() {
+                    return Ok(Self::Repair(kind));
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(Self::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+/// Repairs gear for a fee proportional to its cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blacksmith;
+
+impl Blacksmith {
+    pub fn menu(&self, gold: usize, msg: &str) -> RepairTransaction {
+        let mut buf = String::with_capacity(1 << 7);
+        println!("---- Visiting the blacksmith... ----");
+        println!("{}", msg);
+        println!("e.g. `repair sword` or `repair all`; `q` to leave");
+        let n = msg.lines().count() + 3;
+        loop {
+            String::clear(&mut buf);
+            print!("(💰: {}) ⚒️ ", gold);
+            io::Write::flush(&mut io::stdout()).unwrap();
+
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            match handle.read_line(&mut buf) {
+                Ok(_) => {
+                    let _ = crate::readline::clear_last_n_lines(1);
+                }
+                Err(e) => println!("Error in blacksmith menu readline: {:#?}", e),
+            }
+            if let Ok(transaction) = buf.parse::<RepairTransaction>() {
+                let _ = crate::readline::clear_last_n_lines(n);
+                break transaction;
+            }
+        }
+    }
+    pub fn visit(&self, player: &mut Player) -> bool {
+        let transaction = self.menu(player.gold, &player.repair_message());
+        if transaction == RepairTransaction::Quit {
+            return false;
+        }
+        let kind = transaction.kind();
+        let cost = player.repair_cost(kind);
+        if cost == 0 {
+            println!("There is nothing to repair!");
+        } else if player.gold < cost {
+            println!("Blacksmith rejected transaction: insufficient gold!");
+        } else {
+            player.gold -= cost;
+            player.repair(kind);
+            println!("Your gear was repaired for {} gold.", cost);
+        }
+        true
+    }
+    pub fn work(&self, player: &mut Player) {
+        while self.visit(player) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "r all".parse::<RepairTransaction>().unwrap(),
+            RepairTransaction::RepairAll
+        );
+        assert_eq!(
+            "repair Axe".parse::<RepairTransaction>().unwrap(),
+            RepairTransaction::Repair(Gear::Axe)
+        );
+        assert_eq!(
+            "q".parse::<RepairTransaction>().unwrap(),
+            RepairTransaction::Quit
+        );
+        assert!("repair".parse::<RepairTransaction>().is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{
+    equipment::*,
+    item::equipment_bag::EquipmentBag,
+    resource::*,
+    spell::{Defense, Offense},
+};
+use regex::Regex;
+use std::{
+    fmt,
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Class {
+    Warrior,
+    Mage,
+    Rogue,
+    Cleric,
+}
+pub use Class::*;
+
+impl Class {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Warrior => "Strong and sturdy; bashes and stuns monsters",
+            Mage => "Master of the arcane; deep mana reserves",
+            Rogue => "Quick and balanced; steals gold from monsters",
+            Cleric => "Devout healer; holy light harms and mends",
+        }
+    }
+    /// The value of a stat at the given level, starting from `base` at level 1
+    /// and growing by `num / den` for each level thereafter.
+    const fn growth(level: usize, base: i64, num: i64, den: i64) -> i64 {
+        base + (level as i64 - 1) * num / den
+    }
+    pub const fn strength(&self, level: usize) -> i64 {
+        match self {
+            Warrior => Self::growth(level, 2, 1, 1),
+            Mage => Self::growth(level, 1, 1, 2),
+            Rogue => Self::growth(level, 1, 1, 1),
+            Cleric => Self::growth(level, 1, 2, 3),
+        }
+    }
+    pub const fn intellect(&self, level: usize) -> i64 {
+        match self {
+            Warrior => Self::growth(level, 1, 1, 2),
+            Mage => Self::growth(level, 2, 1, 1),
+            Rogue => Self::growth(level, 1, 1, 1),
+            Cleric => Self::growth(level, 1, 1, 1),
+        }
+    }
+    pub const fn max_hp(&self, level: usize) -> i64 {
+        match self {
+            Warrior => Self::growth(level, 120, 120, 1),
+            Mage => Self::growth(level, 80, 80, 1),
+            Rogue => Self::growth(level, PLAYER_HP, PLAYER_HP, 1),
+            Cleric => Self::growth(level, PLAYER_HP, PLAYER_HP, 1),
+        }
+    }
+    pub const fn max_mp(&self, level: usize) -> i64 {
+        match self {
+            Warrior => Self::growth(level, 60, 5, 1),
+            Mage => Self::growth(level, 150, 15, 1),
+            Rogue => Self::growth(level, 80, 5, 1),
+            Cleric => Self::growth(level, 120, 10, 1),
+        }
+    }
+    pub fn starting_gear(&self) -> EquipmentBag {
+        match self {
+            Warrior => [
+                (Axe, 1),
+                (Helmet, 1),
+                (Breastplate, 1),
+                (Greaves, 1),
+                (Gauntlet, 1),
+            ]
+            .into_iter()
+            .collect(),
+            Mage => [(Staff, 1), (Hat, 1), (Robe, 1), (Breeches, 1), (Glove, 1)]
+                .into_iter()
+                .collect(),
+            Rogue => [(Sword, 1), (Hat, 1), (Breeches, 1), (Gauntlet, 1)]
+                .into_iter()
+                .collect(),
+            Cleric => [(Wand, 1), (Shield, 1), (Helmet, 1), (Robe, 1), (Glove, 1)]
+                .into_iter()
+                .collect(),
+        }
+    }
+    pub const fn offense_spells(&self) -> &'static [Offense] {
+        match self {
+            Warrior => &[],
+            Mage => &[Offense::Fire, Offense::Stone],
+            Rogue => &[Offense::Stone],
+            Cleric => &[Offense::Stone],
+        }
+    }
+    pub const fn defense_spells(&self) -> &'static [Defense] {
+        match self {
+            Warrior => &[Defense::Cure1],
+            Mage => &[Defense::Cure1, Defense::Meditate],
+            Rogue => &[Defense::Cure1],
+            Cleric => &[Defense::Cure1, Defense::Cure2, Defense::Meditate],
+        }
+    }
+    pub const fn ability(&self) -> Ability {
+        match self {
+            Warrior => Ability::ShieldBash,
+            Mage => Ability::ArcaneBlast,
+            Rogue => Ability::Steal,
+            Cleric => Ability::HolyLight,
+        }
+    }
+    pub(crate) fn print_menu_item(&self) {
+        println!(
+            "    {:<30} | {:<30}",
+            format!("{}", self),
+            self.description(),
+        );
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warrior => write!(f, "{}arrior", "W".bold().underline()),
+            Mage => write!(f, "{}age", "M".bold().underline()),
+            Rogue => write!(f, "{}ogue", "R".bold().underline()),
+            Cleric => write!(f, "{}leric", "C".bold().underline()),
+        }
+    }
+}
+
+impl FromStr for Class {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        static RE_WARRIOR: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:warrior|w)$").unwrap());
+        static RE_MAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:mage|m)$").unwrap());
+        static RE_ROGUE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:rogue|r)$").unwrap());
+        static RE_CLERIC: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:cleric|c)$").unwrap());
+
+        if RE_WARRIOR.is_match(s) {
+            Ok(Warrior)
+        } else if RE_MAGE.is_match(s) {
+            Ok(Mage)
+        } else if RE_ROGUE.is_match(s) {
+            Ok(Rogue)
+        } else if RE_CLERIC.is_match(s) {
+            Ok(Cleric)
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
+pub fn class_menu() -> Class {
+    let mut buf = String::with_capacity(1 << 7);
+    println!("==== Choose your class... ====");
+    Warrior.print_menu_item();
+    Mage.print_menu_item();
+    Rogue.print_menu_item();
+    Cleric.print_menu_item();
+    loop {
+        String::clear(&mut buf);
+        print!("🎭 ");
+        io::Write::flush(&mut io::stdout()).unwrap();
+
+        let stdin = io::stdin();
+        let mut handle = stdin.lock();
+        match handle.read_line(&mut buf) {
+            Ok(_) => {
+                let _ = crate::readline::clear_last_n_lines(1);
+            }
+            Err(e) => println!("Error in class menu readline: {:#?}", e),
+        }
+
+        let s = buf.trim();
+        if let Ok(class) = s.parse::<Class>() {
+            let _ = crate::readline::clear_last_n_lines(5);
+            return class;
+        }
+    }
+}
+
+/// A combat technique available only to a single class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Ability {
+    /// Warrior: strikes the monster and stuns it
+    ShieldBash,
+    /// Mage: a devastating burst of arcane energy
+    ArcaneBlast,
+    /// Rogue: steals gold from the monster, once per encounter
+    Steal,
+    /// Cleric: damages the monster and heals the player by the same amount
+    HolyLight,
+}
+
+impl Ability {
+    pub const fn tp_cost(&self) -> i64 {
+        match self {
+            Ability::ShieldBash => 40,
+            Ability::Steal => 20,
+            _ => 0,
+        }
+    }
+    pub const fn mp_cost(&self) -> i64 {
+        match self {
+            Ability::ArcaneBlast => 40,
+            Ability::HolyLight => 30,
+            _ => 0,
+        }
+    }
+    /// Damage per point of the relevant stat.
+    pub const fn damage(&self) -> i64 {
+        match self {
+            Ability::ShieldBash => 20,
+            Ability::ArcaneBlast => 60,
+            Ability::HolyLight => 25,
+            Ability::Steal => 0,
+        }
+    }
+    pub fn description(&self) -> String {
+        match self {
+            Ability::ShieldBash => format!(
+                "{} damage per STR and stuns; costs {} {}",
+                self.damage(),
+                self.tp_cost(),
+                Technical::TP
+            ),
+            Ability::ArcaneBlast => format!(
+                "{} damage per INT; costs {} {}",
+                self.damage(),
+                self.mp_cost(),
+                Mana::MP
+            ),
+            Ability::Steal => format!(
+                "steals gold once per encounter; costs {} {}",
+                self.tp_cost(),
+                Technical::TP
+            ),
+            Ability::HolyLight => format!(
+                "{} damage per INT and heals as much {}; costs {} {}",
+                self.damage(),
+                Health::HP,
+                self.mp_cost(),
+                Mana::MP
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Ability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ability::ShieldBash => write!(f, "{}", "Shield Bash".rgb(0xcd, 0x85, 0x3f)),
+            Ability::ArcaneBlast => write!(f, "{}", "Arcane Blast".rgb(0x93, 0x70, 0xdb)),
+            Ability::Steal => write!(f, "{}", "Steal".rgb(0x69, 0x69, 0x69)),
+            Ability::HolyLight => write!(f, "{}", "Holy Light".rgb(0xff, 0xd7, 0x00)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!("w".parse::<Class>().unwrap(), Warrior);
+        assert_eq!("Mage".parse::<Class>().unwrap(), Mage);
+        assert_eq!("ROGUE".parse::<Class>().unwrap(), Rogue);
+        assert_eq!("c".parse::<Class>().unwrap(), Cleric);
+        assert!("x".parse::<Class>().is_err());
+    }
+
+    #[test]
+    fn growth() {
+        for class in [Warrior, Mage, Rogue, Cleric] {
+            assert!(class.strength(1) >= 1);
+            assert!(class.intellect(1) >= 1);
+            assert!(class.strength(10) > class.strength(1));
+            assert!(class.max_hp(10) > class.max_hp(1));
+        }
+    }
+}
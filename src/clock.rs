@@ -0,0 +1,110 @@
+use std::fmt;
+use yansi::Paint;
+
+pub const MINUTES_PER_HOUR: usize = 60;
+pub const MINUTES_PER_DAY: usize = 24 * MINUTES_PER_HOUR;
+/// Hour at which the sun rises.
+pub const DAWN: usize = 6;
+/// Hour at which the sun sets.
+pub const DUSK: usize = 20;
+/// Hour at which the player wakes after a night at the inn.
+pub const WAKE_HOUR: usize = 8;
+/// Minutes taken by each step through a maze.
+pub const STEP_MINUTES: usize = 5;
+/// Minutes taken by a trip through a dungeon.
+pub const DUNGEON_MINUTES: usize = 2 * MINUTES_PER_HOUR;
+/// Minutes the player must spend awake before they can sleep again.
+pub const TIRED_MINUTES: usize = 6 * MINUTES_PER_HOUR;
+
+/// In-game time, counted in minutes from midnight of the first day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Clock {
+    now: usize,
+    /// When the player last woke up.
+    woke: usize,
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        let now = WAKE_HOUR * MINUTES_PER_HOUR;
+        Self { now, woke: now }
+    }
+    /// Starting from 1.
+    pub fn day(&self) -> usize {
+        self.now / MINUTES_PER_DAY + 1
+    }
+    pub fn hour(&self) -> usize {
+        self.now % MINUTES_PER_DAY / MINUTES_PER_HOUR
+    }
+    pub fn minute(&self) -> usize {
+        self.now % MINUTES_PER_HOUR
+    }
+    /// Whether the player has been up long enough to sleep again.
+    pub fn is_tired(&self) -> bool {
+        self.now - self.woke >= TIRED_MINUTES
+    }
+    pub fn is_night(&self) -> bool {
+        let hour = self.hour();
+        !(DAWN..DUSK).contains(&hour)
+    }
+    pub fn advance(&mut self, minutes: usize) {
+        self.now += minutes;
+    }
+    /// Sleep until the next `WAKE_HOUR`.
+    pub fn sleep(&mut self) {
+        let wake = WAKE_HOUR * MINUTES_PER_HOUR;
+        let now = self.now % MINUTES_PER_DAY;
+        let minutes = if now < wake {
+            wake - now
+        } else {
+            MINUTES_PER_DAY - now + wake
+        };
+        self.advance(minutes);
+        self.woke = self.now;
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let icon = if self.is_night() { "🌙" } else { "🌞" };
+        write!(
+            f,
+            "{icon} Day {}, {:02}:{:02}",
+            self.day().bold(),
+            self.hour(),
+            self.minute()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sleep() {
+        let mut clock = Clock::new();
+        assert_eq!((clock.day(), clock.hour()), (1, WAKE_HOUR));
+        assert!(!clock.is_night() && !clock.is_tired());
+        clock.advance(13 * MINUTES_PER_HOUR);
+        assert!(clock.is_night() && clock.is_tired());
+        clock.sleep();
+        assert!(!clock.is_tired());
+        assert_eq!(
+            (clock.day(), clock.hour(), clock.minute()),
+            (2, WAKE_HOUR, 0)
+        );
+        clock.advance(18 * MINUTES_PER_HOUR + 30);
+        clock.sleep();
+        assert_eq!(
+            (clock.day(), clock.hour(), clock.minute()),
+            (3, WAKE_HOUR, 0)
+        );
+    }
+}
@@ -8,6 +8,7 @@ pub enum CombatAction {
     Run,
     ShowInventory,
     Cast,
+    Skill,
     DoNothing,
 }
 pub use CombatAction::*;
@@ -23,6 +24,8 @@ impl FromStr for CombatAction {
         static RE_INV: LazyLock<Regex> =
             LazyLock::new(|| Regex::new("(?i)^(?:inventory|i)$").unwrap());
         static RE_CAST: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:cast|c)$").unwrap());
+        static RE_SKILL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:skill|s)$").unwrap());
         static RE_NOOP: LazyLock<Regex> =
             LazyLock::new(|| Regex::new(r"(?i)^(?:nothing|n)$").unwrap());
 
@@ -34,6 +37,8 @@ impl FromStr for CombatAction {
             Ok(ShowInventory)
         } else if RE_RUN.is_match(s) {
             Ok(Run)
+        } else if RE_SKILL.is_match(s) {
+            Ok(Skill)
         } else if RE_NOOP.is_match(s) {
             Ok(DoNothing)
         } else {
@@ -54,6 +59,7 @@ impl fmt::Display for CombatAction {
             Run => arm!("{}un", "R"),
             ShowInventory => arm!("{}nventory", "I"),
             Cast => arm!("{}ast", "C"),
+            Skill => arm!("{}kill", "S"),
             DoNothing => arm!("{}othing", "N"),
         }
     }
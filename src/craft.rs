@@ -0,0 +1,292 @@
+use crate::{
+    consumable::*, equipment::*, material::*, multiset::MultiSet, player::Player, utils::is_quit,
+};
+use regex::Regex;
+use std::{
+    fmt::{self, Write as _},
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+/// What a recipe produces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Product {
+    Consumable(Consumable, usize),
+    Gear(Piece),
+}
+
+impl fmt::Display for Product {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Consumable(item, count) => write!(f, "{count} {item}"),
+            Self::Gear(piece) => write!(f, "{} {piece}", piece.rarity),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Recipe {
+    Draught,
+    Nectar,
+    Remedy,
+    Smoke,
+    Blast,
+    Ambrosia,
+    Wolfmail,
+    Trollguard,
+    Tuskstaff,
+    Dragonaxe,
+}
+pub use Recipe::*;
+
+impl Recipe {
+    pub(crate) const fn all() -> [Recipe; 10] {
+        [
+            Draught, Nectar, Remedy, Smoke, Blast, Ambrosia, Wolfmail, Trollguard, Tuskstaff,
+            Dragonaxe,
+        ]
+    }
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Draught => "draught",
+            Nectar => "nectar",
+            Remedy => "remedy",
+            Smoke => "smoke",
+            Blast => "blast",
+            Ambrosia => "ambrosia",
+            Wolfmail => "wolfmail",
+            Trollguard => "trollguard",
+            Tuskstaff => "tuskstaff",
+            Dragonaxe => "dragonaxe",
+        }
+    }
+    pub fn ingredients(&self) -> MultiSet<Material> {
+        let ingredients: &[(Material, usize)] = match self {
+            Draught => &[(FrogLeg, 3)],
+            Nectar => &[(BatWing, 3)],
+            Remedy => &[(SnakeSkin, 2)],
+            Smoke => &[(GoblinEar, 2), (BatWing, 2)],
+            Blast => &[(OrcTusk, 2), (Bone, 2)],
+            Ambrosia => &[(FairyDust, 2), (VampireFang, 1)],
+            Wolfmail => &[(WolfPelt, 4)],
+            Trollguard => &[(TrollHide, 3), (Bone, 2)],
+            Tuskstaff => &[(MammothTusk, 2), (FairyDust, 1)],
+            Dragonaxe => &[(DragonScale, 3), (BearClaw, 2)],
+        };
+        ingredients.iter().copied().collect()
+    }
+    pub const fn gold(&self) -> usize {
+        match self {
+            Draught | Nectar | Remedy => 0,
+            Smoke | Blast => 5,
+            Ambrosia => 10,
+            Wolfmail => 20,
+            Trollguard => 40,
+            Tuskstaff => 40,
+            Dragonaxe => 100,
+        }
+    }
+    pub fn product(&self) -> Product {
+        fn piece(kind: Gear, rarity: Rarity, affixes: Vec<Affix>) -> Product {
+            Product::Gear(Piece {
+                rarity,
+                affixes,
+                ..Piece::from(kind)
+            })
+        }
+        match self {
+            Draught => Product::Consumable(HealthPotion, 2),
+            Nectar => Product::Consumable(ManaPotion, 2),
+            Remedy => Product::Consumable(Antidote, 2),
+            Smoke => Product::Consumable(SmokeBomb, 1),
+            Blast => Product::Consumable(Bomb, 2),
+            Ambrosia => Product::Consumable(Elixir, 1),
+            Wolfmail => piece(Breastplate, Rarity::Magic, vec![Affix::MaxHp(15)]),
+            Trollguard => piece(
+                Greaves,
+                Rarity::Rare,
+                vec![Affix::MaxHp(20), Affix::Strength(2)],
+            ),
+            Tuskstaff => piece(
+                Staff,
+                Rarity::Rare,
+                vec![Affix::Intellect(4), Affix::MaxHp(10)],
+            ),
+            Dragonaxe => piece(
+                Axe,
+                Rarity::Legendary,
+                vec![
+                    Affix::FireDamage(8),
+                    Affix::Strength(4),
+                    Affix::Lifesteal(10),
+                ],
+            ),
+        }
+    }
+    pub(crate) fn write_ingredients<T: fmt::Write>(&self, f: &mut T) -> fmt::Result {
+        for (i, (material, count)) in self.ingredients().bag.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{count} {}", material.plural())?;
+        }
+        if self.gold() > 0 {
+            write!(f, ", {} gold", self.gold())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name().rgb(0xcd, 0x85, 0x3f))
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(idx) = s.parse::<usize>() {
+            if let Some(recipe) = Recipe::all().get(idx.wrapping_sub(1)) {
+                return Ok(*recipe);
+            }
+        }
+        Recipe::all()
+            .into_iter()
+            .find(|x| x.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| s.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CraftTransaction {
+    Craft(Recipe),
+    Quit,
+}
+
+impl FromStr for CraftTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_CRAFT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:craft|c)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.split_once(' ') {
+            if RE_CRAFT.is_match(lhs) {
+                if let Ok(recipe) = rhs.parse::<Recipe>() {
+                    return Ok(CraftTransaction::Craft(recipe));
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(CraftTransaction::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+/// Combines monster materials and gold into consumables and gear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecipeBook;
+
+impl RecipeBook {
+    pub fn message(&self, materials: &MaterialBag) -> String {
+        let mut s = String::with_capacity(1 << 10);
+        writeln!(
+            s,
+            "{}:\n                    | {} | {}",
+            "Recipes".bold().underline(),
+            "product".underline(),
+            "ingredients".underline(),
+        )
+        .unwrap();
+        for (i, recipe) in Recipe::all().into_iter().enumerate() {
+            let status = if materials.contains_all(&recipe.ingredients()) {
+                "✅"
+            } else {
+                "  "
+            };
+            write!(
+                s,
+                "{status} {:>2} {:<24} | {:<40} | ",
+                i + 1,
+                format!("{}", recipe),
+                format!("{}", recipe.product()),
+            )
+            .unwrap();
+            recipe.write_ingredients(&mut s).unwrap();
+            s.push('\n');
+        }
+        write!(s, "{}", materials).unwrap();
+        s
+    }
+    pub fn menu(&self, gold: usize, msg: &str) -> CraftTransaction {
+        let mut buf = String::with_capacity(1 << 7);
+        println!("---- Opening the recipe book... ----");
+        println!("{}", msg);
+        let n = msg.lines().count() + 2;
+        loop {
+            String::clear(&mut buf);
+            print!("(💰: {}) 📖 ", gold);
+            io::Write::flush(&mut io::stdout()).unwrap();
+
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            match handle.read_line(&mut buf) {
+                Ok(_) => {
+                    let _ = crate::readline::clear_last_n_lines(1);
+                }
+                Err(e) => println!("Error in recipe book readline: {:#?}", e),
+            }
+            if let Ok(transaction) = buf.parse::<CraftTransaction>() {
+                let _ = crate::readline::clear_last_n_lines(n);
+                break transaction;
+            }
+        }
+    }
+    pub fn visit(&self, player: &mut Player) -> bool {
+        match self.menu(player.gold, &self.message(&player.materials)) {
+            CraftTransaction::Craft(recipe) => {
+                if player.craft(recipe) {
+                    println!("You crafted {}!", recipe.product());
+                }
+                true
+            }
+            CraftTransaction::Quit => false,
+        }
+    }
+    pub fn work(&self, player: &mut Player) {
+        while self.visit(player) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "c 1".parse::<CraftTransaction>().unwrap(),
+            CraftTransaction::Craft(Draught)
+        );
+        assert_eq!(
+            "craft DragonAxe".parse::<CraftTransaction>().unwrap(),
+            CraftTransaction::Craft(Dragonaxe)
+        );
+        assert!("c 0".parse::<CraftTransaction>().is_err());
+        assert!("c 11".parse::<CraftTransaction>().is_err());
+    }
+
+    #[test]
+    fn ingredients() {
+        let mut materials = MaterialBag::new();
+        materials.push_multiple(FrogLeg, 4);
+        assert!(materials.contains_all(&Draught.ingredients()));
+        materials.remove_all(&Draught.ingredients());
+        assert_eq!(materials.n_available(&FrogLeg), 1);
+        assert!(!materials.contains_all(&Draught.ingredients()));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{player::Player, utils::data_dir};
+use regex::Regex;
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, Write},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+/// Where characters who died in hardcore mode are recorded, within the
+/// game's data directory.
+pub const HALL_OF_FAME: &str = "hall_of_fame.txt";
+
+/// What the player loses upon death.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeathRules {
+    /// Percentage of gold lost
+    pub(crate) gold_loss: usize,
+    /// Percentage of experience lost; one never drops below the current level
+    pub(crate) xp_loss: usize,
+    /// Percentage of each consumable left behind in a corpse when dying
+    /// in the open world
+    pub(crate) item_drop: usize,
+    /// Death ends the game. There is no save file yet, so there is none
+    /// to delete either
+    pub(crate) hardcore: bool,
+}
+
+impl DeathRules {
+    pub fn is_hardcore(&self) -> bool {
+        self.hardcore
+    }
+}
+
+impl Default for DeathRules {
+    fn default() -> Self {
+        Mode::Normal.rules()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Mode {
+    Casual,
+    Normal,
+    Hardcore,
+}
+use Mode::*;
+
+impl Mode {
+    pub const fn rules(&self) -> DeathRules {
+        match self {
+            Casual => DeathRules {
+                gold_loss: 0,
+                xp_loss: 0,
+                item_drop: 0,
+                hardcore: false,
+            },
+            Normal => DeathRules {
+                gold_loss: 10,
+                xp_loss: 10,
+                item_drop: 50,
+                hardcore: false,
+            },
+            Hardcore => DeathRules {
+                gold_loss: 0,
+                xp_loss: 0,
+                item_drop: 0,
+                hardcore: true,
+            },
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Casual => "Death costs nothing",
+            Normal => "Lose gold and experience; leave items on your corpse",
+            Hardcore => "Death is permanent",
+        }
+    }
+    pub(crate) fn print_menu_item(&self) {
+        println!(
+            "    {:<30} | {:<30}",
+            format!("{}", self),
+            self.description(),
+        );
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Casual => write!(f, "{}asual", "C".bold().underline()),
+            Normal => write!(f, "{}ormal", "N".bold().underline()),
+            Hardcore => write!(f, "{}ardcore", "H".bold().underline()),
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        static RE_CASUAL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:casual|c)$").unwrap());
+        static RE_NORMAL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:normal|n)$").unwrap());
+        static RE_HARDCORE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:hardcore|h)$").unwrap());
+
+        if RE_CASUAL.is_match(s) {
+            Ok(Casual)
+        } else if RE_NORMAL.is_match(s) {
+            Ok(Normal)
+        } else if RE_HARDCORE.is_match(s) {
+            Ok(Hardcore)
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
+pub fn mode_menu() -> Mode {
+    let mut buf = String::with_capacity(1 << 7);
+    println!("==== Choose how death is treated... ====");
+    Casual.print_menu_item();
+    Normal.print_menu_item();
+    Hardcore.print_menu_item();
+    loop {
+        String::clear(&mut buf);
+        print!("💀 ");
+        io::stdout().flush().unwrap();
+
+        let stdin = io::stdin();
+        let mut handle = stdin.lock();
+        match handle.read_line(&mut buf) {
+            Ok(_) => {
+                let _ = crate::readline::clear_last_n_lines(1);
+            }
+            Err(e) => println!("Error in mode menu readline: {:#?}", e),
+        }
+
+        if let Ok(mode) = buf.parse::<Mode>() {
+            let _ = crate::readline::clear_last_n_lines(4);
+            return mode;
+        }
+    }
+}
+
+/// Append the fallen character to the hall of fame.
+pub fn record(player: &Player) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(data_dir()?.join(HALL_OF_FAME))?;
+    writeln!(
+        file,
+        "{:?}\t{}\t{}\t{}",
+        player.class(),
+        player.level(),
+        player.xp,
+        player.gold
+    )
+}
+
+pub fn hall_of_fame() -> io::Result<String> {
+    let contents = fs::read_to_string(data_dir()?.join(HALL_OF_FAME))?;
+    let mut s = String::with_capacity(1 << 10);
+    s.push_str(&format!(
+        "{}\n    {:<10} | {:>5} | {:>10} | {:>10}\n",
+        "Hall of fame".bold().underline(),
+        "class",
+        "level",
+        "experience",
+        "gold"
+    ));
+    for line in contents.lines() {
+        let fields: Vec<_> = line.split('\t').collect();
+        if let [class, level, xp, gold] = fields[..] {
+            s.push_str(&format!(
+                "    {:<10} | {:>5} | {:>10} | {:>10}\n",
+                class, level, xp, gold
+            ));
+        }
+    }
+    Ok(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!("c".parse::<Mode>().unwrap(), Casual);
+        assert_eq!("Normal".parse::<Mode>().unwrap(), Normal);
+        assert_eq!("HARDCORE".parse::<Mode>().unwrap(), Hardcore);
+        assert!("x".parse::<Mode>().is_err());
+    }
+}
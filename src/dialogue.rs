@@ -0,0 +1,543 @@
+//! Conversations with the people of the world, written as plain text:
+//!
+//! ```text
+//! # Comments start with a hash.
+//! name Hermit
+//!
+//! node start
+//! say Ah, a traveller!
+//! choice [not flag gift] Have you anything to spare? -> gift
+//! choice Farewell. -> end
+//!
+//! node gift
+//! effect give 2 health potion
+//! effect flag gift
+//! say Take these.
+//! choice Thank you. -> start
+//! ```
+//!
+//! The conversation begins at the first node. Effects apply, in order, each
+//! time a node is reached; should any of them be unable to, none apply and
+//! the conversation ends. Choices are offered only if all of their
+//! conditions hold, and `end` leaves.
+
+use crate::{
+    equipment::Piece,
+    item::Item,
+    maze::{Element, MazeGraph},
+    player::Player,
+    quest::{Objective, Quest, Reward, MAX_ACTIVE_QUESTS},
+    utils::is_quit,
+};
+use indexmap::IndexMap;
+use regex::Regex;
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, BufRead},
+    path::Path,
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Level(usize),
+    Gold(usize),
+    Has(Item, usize),
+    Flag(String),
+    NotFlag(String),
+}
+
+impl Condition {
+    pub fn holds(&self, player: &Player) -> bool {
+        match self {
+            Self::Level(n) => player.level >= *n,
+            Self::Gold(n) => player.gold >= *n,
+            Self::Has(item, n) => player.n_available(item) >= *n,
+            Self::Flag(flag) => player.flags.contains(flag),
+            Self::NotFlag(flag) => !player.flags.contains(flag),
+        }
+    }
+}
+
+/// e.g. `level 3`, `gold 50`, `has 2 health potion`, `flag met_hermit`, `not flag met_hermit`
+impl FromStr for Condition {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_LEVEL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^level\s+(\d+)$").unwrap());
+        static RE_GOLD: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^gold\s+(\d+)$").unwrap());
+        static RE_HAS: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^has\s+(?:(\d+)\s+)?(.+)$").unwrap());
+        static RE_FLAG: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^(not\s+)?flag\s+(\w+)$").unwrap());
+
+        let s = s.trim();
+        if let Some(caps) = RE_LEVEL.captures(s) {
+            if let Ok(n) = caps[1].parse() {
+                return Ok(Self::Level(n));
+            }
+        } else if let Some(caps) = RE_GOLD.captures(s) {
+            if let Ok(n) = caps[1].parse() {
+                return Ok(Self::Gold(n));
+            }
+        } else if let Some(caps) = RE_HAS.captures(s) {
+            let n = caps.get(1).map_or(Ok(1), |n| n.as_str().parse());
+            if let (Ok(n), Ok(item)) = (n, caps[2].parse::<Item>()) {
+                return Ok(Self::Has(item, n));
+            }
+        } else if let Some(caps) = RE_FLAG.captures(s) {
+            let flag = caps[2].to_string();
+            return Ok(if caps.get(1).is_some() {
+                Self::NotFlag(flag)
+            } else {
+                Self::Flag(flag)
+            });
+        }
+        Err(s.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    Give(Item, usize),
+    /// Negative amounts are paid by the player
+    Gold(i64),
+    /// Restore all HP and MP
+    Heal,
+    Flag(String),
+    Quest(Objective),
+    /// Connect two tiles of the world with a pair of portals
+    Portal {
+        src: (usize, (usize, usize)),
+        dst: (usize, (usize, usize)),
+    },
+}
+
+impl Effect {
+    /// Why the effect could not take place, found without changing anything.
+    pub fn can_apply(&self, player: &Player, graph: &MazeGraph) -> Result<(), String> {
+        let is_vacant = |(i, pos): (usize, (usize, usize))| {
+            graph.0.get(i).is_some_and(|maze| {
+                maze.grid.check_bounds(pos)
+                    && matches!(maze.grid[pos], Element::Empty | Element::InactivePortal)
+            })
+        };
+        match self {
+            Self::Gold(n) if *n < 0 && player.gold < n.unsigned_abs() as usize => {
+                Err(format!("You do not have {} gold!", n.unsigned_abs()))
+            }
+            Self::Quest(_) if player.journal.0.len() >= MAX_ACTIVE_QUESTS => Err(format!(
+                "You may only pursue {MAX_ACTIVE_QUESTS} quests at once!"
+            )),
+            Self::Portal { src, dst } if !is_vacant(*src) || !is_vacant(*dst) => {
+                Err("The mirror does not stir.".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Check `can_apply` first.
+    pub fn apply(&self, player: &mut Player, graph: &mut MazeGraph) {
+        match self {
+            Self::Give(item, n) => {
+                match *item {
+                    Item::Consumable(x) => player.inventory.push_multiple(x, *n),
+                    Item::Gear(x) => player.equipment_bag.push_multiple(Piece::from(x), *n),
+                }
+                println!("You received {n} {item}.");
+            }
+            Self::Gold(n) if *n >= 0 => {
+                player.gold += n.unsigned_abs() as usize;
+                println!("You received {n} gold.");
+            }
+            Self::Gold(n) => {
+                let n = n.unsigned_abs() as usize;
+                player.gold -= n;
+                println!("You paid {n} gold.");
+            }
+            Self::Heal => {
+                player.hp.restore_all();
+                player.mp.restore_all();
+                println!("You feel refreshed!");
+            }
+            Self::Flag(flag) => {
+                player.flags.insert(flag.clone());
+            }
+            Self::Quest(objective) => {
+                let reward = Reward::new(*objective, player.level, None);
+                let mut quest = Quest::new(*objective, reward);
+                quest.accept(player);
+                println!("You accepted the quest: {}.", quest.objective);
+                player.journal.0.push(quest);
+            }
+            Self::Portal { src, dst } => {
+                graph.twoway_connect(src.0, src.1, dst.0, dst.1);
+                println!("A mirror flickers to life!");
+            }
+        }
+    }
+}
+
+/// e.g. `give 2 health potion`, `gold -50`, `heal`, `flag met_hermit`,
+/// `quest kill 3 wolves`, `portal 0 (19, 0) -> 1 (2, 2)`
+impl FromStr for Effect {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_GIVE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^give\s+(?:(\d+)\s+)?(.+)$").unwrap());
+        static RE_GOLD: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^gold\s+(-?\d+)$").unwrap());
+        static RE_HEAL: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^heal$").unwrap());
+        static RE_FLAG: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^flag\s+(\w+)$").unwrap());
+        static RE_QUEST: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^quest\s+(.+)$").unwrap());
+        static RE_PORTAL: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(
+                r"(?i)^portal\s+(\d+)\s*\(\s*(\d+)\s*,\s*(\d+)\s*\)\s*->\s*(\d+)\s*\(\s*(\d+)\s*,\s*(\d+)\s*\)$",
+            )
+            .unwrap()
+        });
+
+        let s = s.trim();
+        if let Some(caps) = RE_GIVE.captures(s) {
+            let n = caps.get(1).map_or(Ok(1), |n| n.as_str().parse());
+            if let (Ok(n), Ok(item)) = (n, caps[2].parse::<Item>()) {
+                return Ok(Self::Give(item, n));
+            }
+        } else if let Some(caps) = RE_GOLD.captures(s) {
+            if let Ok(n) = caps[1].parse() {
+                return Ok(Self::Gold(n));
+            }
+        } else if RE_HEAL.is_match(s) {
+            return Ok(Self::Heal);
+        } else if let Some(caps) = RE_FLAG.captures(s) {
+            return Ok(Self::Flag(caps[1].to_string()));
+        } else if let Some(caps) = RE_QUEST.captures(s) {
+            if let Ok(objective) = caps[1].parse::<Objective>() {
+                return Ok(Self::Quest(objective));
+            }
+        } else if let Some(caps) = RE_PORTAL.captures(s) {
+            let n: Vec<usize> = (1..=6).filter_map(|i| caps[i].parse().ok()).collect();
+            if let [i, i_row, i_col, j, j_row, j_col] = n[..] {
+                return Ok(Self::Portal {
+                    src: (i, (i_row, i_col)),
+                    dst: (j, (j_row, j_col)),
+                });
+            }
+        }
+        Err(s.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub(crate) text: String,
+    pub(crate) conditions: Vec<Condition>,
+    /// `None` ends the conversation
+    pub(crate) next: Option<String>,
+}
+
+impl Choice {
+    pub fn is_available(&self, player: &Player) -> bool {
+        self.conditions.iter().all(|x| x.holds(player))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Node {
+    pub(crate) text: Vec<String>,
+    pub(crate) effects: Vec<Effect>,
+    pub(crate) choices: Vec<Choice>,
+}
+
+impl Node {
+    /// Check every effect before any applies, so that a node never takes
+    /// effect halfway.
+    pub fn can_apply(&self, player: &Player, graph: &MazeGraph) -> Result<(), String> {
+        let paid: usize = self
+            .effects
+            .iter()
+            .map(|x| match x {
+                Effect::Gold(n) if *n < 0 => n.unsigned_abs() as usize,
+                _ => 0,
+            })
+            .sum();
+        if player.gold < paid {
+            return Err(format!("You do not have {paid} gold!"));
+        }
+        self.effects
+            .iter()
+            .try_for_each(|x| x.can_apply(player, graph))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dialogue {
+    pub(crate) name: String,
+    /// The conversation begins at the first node
+    pub(crate) nodes: IndexMap<String, Node>,
+}
+
+impl Dialogue {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        fs::read_to_string(path)
+            .map_err(|e| format!("{}: {e}", path.display()))?
+            .parse()
+    }
+    /// Lives beside the starting glade.
+    pub fn hermit() -> Self {
+        include_str!("../data/dialogue/hermit.txt").parse().unwrap()
+    }
+    fn speech(&self, node: &Node) -> String {
+        let mut s = String::with_capacity(1 << 8);
+        for line in node.text.iter() {
+            writeln!(s, "{}: {}", self.name.bold(), line).unwrap();
+        }
+        s
+    }
+    pub fn message(&self, node: &Node, choices: &[&Choice]) -> String {
+        let mut s = self.speech(node);
+        for (i, choice) in choices.iter().enumerate() {
+            writeln!(s, "    {}. {}", (i + 1).bold().underline(), choice.text).unwrap();
+        }
+        s
+    }
+    /// Returns the index of the chosen reply, or `None` if the player leaves.
+    pub fn menu(&self, msg: &str, n_choices: usize) -> Option<usize> {
+        let mut buf = String::with_capacity(1 << 7);
+        println!("---- Talking to {}... ----", self.name);
+        print!("{}", msg);
+        println!("`1`-`{n_choices}` to reply; `q` to leave");
+        let n = msg.lines().count() + 2;
+        loop {
+            String::clear(&mut buf);
+            print!("💬 ");
+            io::Write::flush(&mut io::stdout()).unwrap();
+
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            match handle.read_line(&mut buf) {
+                Ok(_) => {
+                    let _ = crate::readline::clear_last_n_lines(1);
+                }
+                Err(e) => println!("Error in dialogue menu readline: {:#?}", e),
+            }
+            let s = buf.trim();
+            if is_quit(s) {
+                let _ = crate::readline::clear_last_n_lines(n);
+                return None;
+            } else if let Ok(i @ 1..) = s.parse::<usize>() {
+                if i <= n_choices {
+                    let _ = crate::readline::clear_last_n_lines(n);
+                    return Some(i - 1);
+                }
+            }
+        }
+    }
+    pub fn run(&self, player: &mut Player, graph: &mut MazeGraph) {
+        let Some((_, mut node)) = self.nodes.first() else {
+            return;
+        };
+        loop {
+            if let Err(e) = node.can_apply(player, graph) {
+                println!("{e}");
+                break;
+            }
+            node.effects.iter().for_each(|x| x.apply(player, graph));
+            let choices: Vec<&Choice> = node
+                .choices
+                .iter()
+                .filter(|x| x.is_available(player))
+                .collect();
+            if choices.is_empty() {
+                print!("{}", self.speech(node));
+                break;
+            }
+            match self.menu(&self.message(node, &choices), choices.len()) {
+                Some(i) => match &choices[i].next {
+                    Some(key) => node = &self.nodes[key],
+                    None => break,
+                },
+                None => break,
+            }
+        }
+    }
+}
+
+impl FromStr for Dialogue {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^name\s+(.+)$").unwrap());
+        static RE_NODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^node\s+(\w+)$").unwrap());
+        static RE_SAY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^say\s+(.+)$").unwrap());
+        static RE_EFFECT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"^effect\s+(.+)$").unwrap());
+        static RE_CHOICE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^choice\s+((?:\[[^\]]*\]\s*)*)(.+?)\s*->\s*(\w+)$").unwrap()
+        });
+        static RE_CONDITION: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"\[([^\]]*)\]").unwrap());
+
+        let mut name = None;
+        let mut nodes: IndexMap<String, Node> = IndexMap::new();
+        for (i, line) in s.lines().enumerate() {
+            let err = |e: &str| format!("line {}: {e}", i + 1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(caps) = RE_NAME.captures(line) {
+                name = Some(caps[1].to_string());
+                continue;
+            } else if let Some(caps) = RE_NODE.captures(line) {
+                let key = caps[1].to_string();
+                if nodes.contains_key(&key) || key == "end" {
+                    return Err(err(&format!("node `{key}` is already defined")));
+                }
+                nodes.insert(key, Node::default());
+                continue;
+            }
+            let Some((_, node)) = nodes.last_mut() else {
+                return Err(err("expected `node` first"));
+            };
+            if let Some(caps) = RE_SAY.captures(line) {
+                node.text.push(caps[1].to_string());
+            } else if let Some(caps) = RE_EFFECT.captures(line) {
+                let effect = caps[1]
+                    .parse::<Effect>()
+                    .map_err(|e| err(&format!("unknown effect `{e}`")))?;
+                node.effects.push(effect);
+            } else if let Some(caps) = RE_CHOICE.captures(line) {
+                let conditions = RE_CONDITION
+                    .captures_iter(&caps[1])
+                    .map(|x| {
+                        x[1].parse::<Condition>()
+                            .map_err(|e| err(&format!("unknown condition `{e}`")))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let next = Some(caps[3].to_string()).filter(|x| x != "end");
+                node.choices.push(Choice {
+                    text: caps[2].to_string(),
+                    conditions,
+                    next,
+                });
+            } else {
+                return Err(err(&format!("cannot parse `{line}`")));
+            }
+        }
+        for choice in nodes.values().flat_map(|x| x.choices.iter()) {
+            if let Some(key) = &choice.next {
+                if !nodes.contains_key(key) {
+                    return Err(format!(
+                        "choice `{}` leads to unknown node `{key}`",
+                        choice.text
+                    ));
+                }
+            }
+        }
+        if nodes.is_empty() {
+            return Err("no nodes".to_string());
+        }
+        Ok(Self {
+            name: name.ok_or("missing `name`")?,
+            nodes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{consumable::HealthPotion, monster::MonsterKind::Wolf};
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            Dialogue::load("data/dialogue/hermit.txt"),
+            Ok(Dialogue::hermit())
+        );
+        let hermit = Dialogue::hermit();
+        assert_eq!(hermit.name, "Hermit");
+        assert_eq!(hermit.nodes.get_index(0).unwrap().0, "start");
+        assert_eq!(
+            hermit.nodes["start"].choices[2].conditions,
+            vec![
+                Condition::Level(2),
+                Condition::NotFlag("hermit_wolves".into())
+            ]
+        );
+        assert_eq!(
+            hermit.nodes["accept"].effects[0],
+            Effect::Quest(Objective::Kill {
+                kind: Wolf,
+                count: 3
+            })
+        );
+        assert_eq!(
+            hermit.nodes["portal"].effects[1],
+            Effect::Portal {
+                src: (0, (19, 0)),
+                dst: (0, (0, 19))
+            }
+        );
+        assert!("name X\nsay Hello".parse::<Dialogue>().is_err());
+        assert!("name X\nnode a\nchoice Bye -> b"
+            .parse::<Dialogue>()
+            .is_err());
+        assert!("name X\nnode a\neffect fly".parse::<Dialogue>().is_err());
+        assert!("name X\nnode a\nchoice [nonsense] Bye -> end"
+            .parse::<Dialogue>()
+            .is_err());
+    }
+
+    #[test]
+    fn effects() {
+        let mut player = Player::new(crate::class::Class::Warrior);
+        let mut graph = MazeGraph::new_demo();
+        let hermit = Dialogue::hermit();
+        let start = &hermit.nodes["start"];
+        let available = |player: &Player| {
+            start
+                .choices
+                .iter()
+                .filter(|x| x.is_available(player))
+                .count()
+        };
+        assert_eq!(available(&player), 3);
+
+        let potions = player.inventory.n_available(&HealthPotion);
+        for effect in hermit.nodes["gift"].effects.iter() {
+            assert!(effect.can_apply(&player, &graph).is_ok());
+            effect.apply(&mut player, &mut graph);
+        }
+        assert_eq!(player.inventory.n_available(&HealthPotion), potions + 2);
+        assert_eq!(available(&player), 2);
+
+        // Too poor to pay, so not even the portal opens
+        player.gold = 0;
+        let node = &hermit.nodes["portal"];
+        assert!(node.can_apply(&player, &graph).is_err());
+        player.gold = 50;
+        assert!(node.can_apply(&player, &graph).is_ok());
+        node.effects
+            .iter()
+            .for_each(|x| x.apply(&mut player, &mut graph));
+        assert!(matches!(graph.0[0].grid[(19, 0)], Element::ActivePortal(_)));
+        assert_eq!(player.gold, 0);
+        player.gold = 50;
+        assert!(node.can_apply(&player, &graph).is_err());
+
+        let accept = &hermit.nodes["accept"];
+        assert!(accept.can_apply(&player, &graph).is_ok());
+        accept
+            .effects
+            .iter()
+            .for_each(|x| x.apply(&mut player, &mut graph));
+        assert_eq!(player.journal.0.len(), 1);
+    }
+}
@@ -23,14 +23,21 @@ impl<'a> Dungeon<'a> {
         println!("Let the gauntlet commence!");
         println!("{DELIM}\n\n\n");
         let n = self.n_monster;
+        let mut victories = 0;
         for _ in 0..n {
             let mut enc = Encounter::rand(&mut self.player);
             match enc.run() {
-                PlayerVictory => self.scoreboard.record(enc.monster.kind),
+                PlayerVictory => {
+                    self.scoreboard.record(enc.monster.kind);
+                    victories += 1;
+                }
                 MonsterVictory => break,
                 _ => (),
             }
         }
+        if victories == n {
+            self.player.journal.clear_dungeon();
+        }
         println!("\n\n\n{}", DELIM);
         print!("{}", self.scoreboard);
         println!("{DELIM}\n\n\n");
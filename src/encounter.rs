@@ -1,10 +1,26 @@
 use crate::{
-    combat::*, loot::*, melee::*, monster::*, player::*, resource::Mana, resource::Technical,
+    accessory::SeersAmulet,
+    class::Ability,
+    combat::*,
+    consumable::{ItemEffect, Setting},
+    loot::*,
+    melee::*,
+    monster::*,
+    player::*,
+    resource::*,
     spell::*,
+    utils::confirm,
+};
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, Write},
 };
-use std::io::{self, BufRead, Write};
 use yansi::Paint;
 
+/// Monster attacks after a stun during which the player cannot be stunned
+/// again.
+pub const STUN_IMMUNITY: usize = 3;
+
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum EncounterOutcome {
     PlayerVictory,
@@ -17,6 +33,12 @@ pub use EncounterOutcome::*;
 pub struct Encounter<'a> {
     pub(crate) player: &'a mut Player,
     pub(crate) monster: Monster,
+    pub(crate) combo: ComboCounter,
+    pub(crate) player_stunned: bool,
+    pub(crate) monster_stunned: bool,
+    /// Monster attacks left before the player can be stunned again
+    pub(crate) stun_immunity: usize,
+    pub(crate) stolen: bool,
     buf: String,
     status: String,
 }
@@ -34,6 +56,11 @@ impl<'a> Encounter<'a> {
         Self {
             player,
             monster: Monster::rand(level),
+            combo: ComboCounter::new(),
+            player_stunned: false,
+            monster_stunned: false,
+            stun_immunity: 0,
+            stolen: false,
             buf: String::with_capacity(1 << 7),
             status: String::with_capacity(1 << 7),
         }
@@ -43,6 +70,11 @@ impl<'a> Encounter<'a> {
         Self {
             player,
             monster: Monster::rand_level(kind, level),
+            combo: ComboCounter::new(),
+            player_stunned: false,
+            monster_stunned: false,
+            stun_immunity: 0,
+            stolen: false,
             buf: String::with_capacity(1 << 7),
             status: String::with_capacity(1 << 7),
         }
@@ -65,13 +97,13 @@ impl<'a> Encounter<'a> {
         match res {
             PlayerVictory => {
                 println!("---- The {kind} died! ----");
-                let loot = Loot::rand_weighted(self.monster.kind);
+                self.player.scoreboard.record(kind);
+                let loot =
+                    Loot::rand_weighted(self.monster.kind, self.monster.level, self.player.luck());
                 loot.announce();
-                self.player.acquire(loot);
-                let xp = self.monster.experience_points();
+                self.player.pick_up(loot);
+                let xp = self.player.gain_xp(self.monster.experience_points());
                 println!("You earned {} experience points!", xp.bold());
-                self.player.xp += xp;
-                self.player.update_level();
             }
             PlayerRan => {
                 println!("---- You ran away! ----");
@@ -83,21 +115,158 @@ impl<'a> Encounter<'a> {
         res
     }
 
+    /// With a `SeersAmulet`, the player sees the monster and may decline to
+    /// fight it. Returns `true` if the encounter should proceed.
+    pub fn scout(&self) -> bool {
+        if !self.player.equipment.wears(SeersAmulet) {
+            return true;
+        }
+        let msg = format!(
+            "Your {} reveals a level {} {} with {}. Engage?",
+            SeersAmulet,
+            self.monster.level,
+            self.monster.kind,
+            self.monster.status()
+        );
+        confirm(&msg)
+    }
+
+    pub(crate) fn apply_combo(&mut self, combo: Combo, melee: &mut MeleeAttack) {
+        println!("---- {combo} combo! ----");
+        match combo.effect() {
+            ComboEffect::Damage(pct) => melee.apply_bonus(pct),
+            ComboEffect::Technical(amount) => {
+                self.player.restore_tp(amount);
+                println!("You recover {} {}!", amount.magenta(), Technical::TP);
+            }
+            ComboEffect::Stun => self.monster_stunned = true,
+        }
+    }
+
+    /// Fire damage, enchantments and lifesteal from the player's gear, after a melee
+    /// attack which dealt `dealt` damage.
+    pub(crate) fn weapon_effects(&mut self, dealt: i64) {
+        let fire = self.player.equipment.fire_damage();
+        if fire > 0 {
+            println!(
+                "Flames burn the {} for {} damage!",
+                self.monster.kind,
+                fire.magenta()
+            );
+            self.monster.receive_damage(fire);
+        }
+        let weapon = self.player.equipment.weapon();
+        if let Some(element) = weapon.enchantment {
+            let amount = self
+                .monster
+                .elemental_damage(element, weapon.enchantment_damage());
+            if amount > 0 {
+                println!(
+                    "Your {element} enchantment hits the {} for {} damage!",
+                    self.monster.kind,
+                    amount.magenta()
+                );
+                self.monster.receive_damage(amount);
+            }
+        }
+        let heal = dealt * self.player.equipment.lifesteal() / 100;
+        if heal > 0 {
+            println!("You drain {} {}!", heal.magenta(), Health::HP);
+            self.player.restore_hp(heal);
+        }
+    }
+    /// Returns `false` if the ability could not be used, in which case
+    /// the player has not spent their turn.
+    pub(crate) fn use_ability(&mut self) -> bool {
+        let ability = self.player.class().ability();
+        if ability == Ability::Steal && (self.stolen || self.monster.purse() == 0) {
+            println!("The {} has nothing to steal!", self.monster.kind);
+            return false;
+        }
+        if !self.player.use_ability(ability) {
+            if self.player.tp.current < ability.tp_cost() {
+                println!("Insufficient {}!", Technical::TP);
+            } else {
+                println!("Insufficient {}!", Mana::MP);
+            }
+            return false;
+        }
+        match ability {
+            Ability::ShieldBash => {
+                let amount = self.player.strength() * ability.damage();
+                self.monster.receive_ability_attack(ability, amount);
+                self.monster_stunned = true;
+            }
+            Ability::ArcaneBlast => {
+                let amount = self.player.intellect() * ability.damage();
+                self.monster.receive_ability_attack(ability, amount);
+            }
+            Ability::HolyLight => {
+                let amount = self.player.intellect() * ability.damage();
+                self.monster.receive_ability_attack(ability, amount);
+                self.player.restore_hp(amount);
+                println!(
+                    "Your {ability} heals you for {} {}!",
+                    amount.magenta(),
+                    Health::HP
+                );
+            }
+            Ability::Steal => {
+                let gold = self.monster.purse();
+                self.player.gold += gold;
+                self.stolen = true;
+                println!(
+                    "You stole {} gold from the {}!",
+                    gold.bold(),
+                    self.monster.kind
+                );
+            }
+        }
+        true
+    }
+
     pub(crate) fn dialogue(&mut self) -> EncounterOutcome {
         macro_rules! damage_and_check {
             () => {
-                self.player.receive_melee_attack(&mut self.monster);
-                if self.is_player_dead() {
+                if self.monster_stunned {
+                    println!("The {} is stunned!", self.monster.kind);
+                    self.monster_stunned = false;
+                } else {
+                    let immune = self.stun_immunity > 0;
+                    self.stun_immunity = self.stun_immunity.saturating_sub(1);
+                    if self.player.receive_melee_attack(&mut self.monster) && !immune {
+                        println!("You are stunned!");
+                        self.player_stunned = true;
+                        self.stun_immunity = STUN_IMMUNITY;
+                        self.combo.reset();
+                    }
+                }
+                self.player.end_round();
+                if self.is_player_dead() && !self.player.phoenix_revive() {
                     return MonsterVictory;
                 }
             };
         }
         loop {
+            if self.player_stunned {
+                self.player_stunned = false;
+                damage_and_check!();
+                continue;
+            }
             match self.menu() {
                 Attack => {
-                    if let Some(melee) = melee_menu(self.player.strength()) {
+                    if let Some(melee) = melee_menu(self.player.strength(), &self.player.skills) {
                         match self.player.cast_melee(melee) {
-                            Some(melee) => self.monster.receive_melee_attack(melee),
+                            Some(mut melee) => {
+                                if let Some(combo) = self.combo.push(melee.kind) {
+                                    self.apply_combo(combo, &mut melee);
+                                }
+                                self.player.roll_critical(&mut melee);
+                                let dealt = melee.damage;
+                                self.monster.receive_melee_attack(melee);
+                                self.player.wear_weapon();
+                                self.weapon_effects(dealt);
+                            }
                             None => {
                                 println!("Insufficient {}!", Technical::TP);
                                 continue;
@@ -110,7 +279,11 @@ impl<'a> Encounter<'a> {
                     }
                 }
                 Cast => {
-                    if let Some(spell) = spell_menu(self.player.intellect()) {
+                    if let Some(spell) = spell_menu(
+                        self.player.intellect(),
+                        self.player.class(),
+                        &self.player.skills,
+                    ) {
                         match self.player.cast_spell(spell) {
                             Some(SpellCast::Offense(x)) => self.monster.receive_spell_attack(x),
                             Some(SpellCast::Defense(x)) => self.player.receive_defensive_spell(x),
@@ -119,22 +292,50 @@ impl<'a> Encounter<'a> {
                                 continue;
                             }
                         }
+                        self.combo.reset();
                         if self.is_monster_dead() {
                             return PlayerVictory;
                         }
                         damage_and_check!();
                     }
                 }
-                ShowInventory => {
-                    if self.player.visit_inventory() {
+                Skill => {
+                    if self.use_ability() {
+                        self.combo.reset();
+                        if self.is_monster_dead() {
+                            return PlayerVictory;
+                        }
                         damage_and_check!();
                     }
                 }
+                ShowInventory => match self.player.visit_inventory(Setting::Combat) {
+                    Some(ItemEffect::Escape) => {
+                        return PlayerRan;
+                    }
+                    Some(effect) => {
+                        self.combo.reset();
+                        if let ItemEffect::Damage(amount) = effect {
+                            println!(
+                                "The explosion hits the {} for {} damage!",
+                                self.monster.kind,
+                                amount.magenta()
+                            );
+                            self.monster.receive_damage(amount);
+                            if self.is_monster_dead() {
+                                return PlayerVictory;
+                            }
+                        }
+                        damage_and_check!();
+                    }
+                    None => (),
+                },
                 Run => {
+                    self.combo.reset();
                     damage_and_check!();
                     return PlayerRan;
                 }
                 DoNothing => {
+                    self.combo.reset();
                     damage_and_check!();
                 }
             }
@@ -144,6 +345,9 @@ impl<'a> Encounter<'a> {
     pub fn update_status(&mut self) {
         String::clear(&mut self.status);
         self.player.write_status(&mut self.status).unwrap();
+        if self.combo.count() > 0 {
+            write!(self.status, " {}", self.combo).unwrap();
+        }
     }
 
     pub fn menu(&mut self) -> CombatAction {
@@ -154,8 +358,14 @@ impl<'a> Encounter<'a> {
             self.monster.status()
         );
         println!(
-            "{}, {}, {}, {}, or do {}?",
-            Attack, Cast, ShowInventory, Run, DoNothing
+            "{}, {}, {} ({}), {}, {}, or do {}?",
+            Attack,
+            Cast,
+            Skill,
+            self.player.class().ability(),
+            ShowInventory,
+            Run,
+            DoNothing
         );
         loop {
             String::clear(&mut self.buf);
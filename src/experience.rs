@@ -0,0 +1,124 @@
+/// The relationship between experience points and level.
+#[derive(Debug, Clone, PartialEq)]
+pub enum XpCurve {
+    /// `level = ⌈cap (1 - exp(-xp / r))⌉`, which saturates at `cap`.
+    Exponential { r: f64, cap: usize },
+    /// Reaching `level` requires `⌊base (level - 1)^exponent⌋` experience.
+    Polynomial {
+        base: f64,
+        exponent: f64,
+        cap: usize,
+    },
+    /// The `i`th entry is the experience required to reach level `i + 1`;
+    /// the cap is the number of entries.
+    Table(Vec<usize>),
+}
+
+// const R: f64 = 18466.496523378733; // -12800.0 / (0.5_f64).ln();
+// const R: f64 = 9233.248261689367; // -6400.0 / (0.5_f64).ln();
+// const R: f64 = 4616.624130844683; // -3200.0 / (0.5_f64).ln();
+const R: f64 = 2308.3120654223417; // -1600.0 / (0.5_f64).ln();
+
+impl XpCurve {
+    /// The original curve, which saturates at level 10.
+    pub const fn classic() -> Self {
+        Self::Exponential { r: R, cap: 10 }
+    }
+    /// Each level beyond the 5th requires twice the experience of the last.
+    pub fn doubling() -> Self {
+        Self::Table(vec![0, 100, 200, 300, 400, 800, 1600, 3200, 6400, 12800])
+    }
+    pub fn cap(&self) -> usize {
+        match self {
+            Self::Exponential { cap, .. } | Self::Polynomial { cap, .. } => (*cap).max(1),
+            Self::Table(thresholds) => thresholds.len().max(1),
+        }
+    }
+    /// The minimum experience at which one is at `level`.
+    pub fn min_xp(&self, level: usize) -> usize {
+        let level = level.clamp(1, self.cap());
+        if level == 1 {
+            return 0;
+        }
+        match self {
+            Self::Exponential { r, cap } => {
+                let y = ((level - 1) as f64) / (*cap as f64);
+                (-((1.0 - y).ln()) * r).floor() as usize + 1
+            }
+            Self::Polynomial { base, exponent, .. } => {
+                (base * ((level - 1) as f64).powf(*exponent)).floor() as usize
+            }
+            Self::Table(thresholds) => thresholds[level - 1],
+        }
+    }
+    pub fn level(&self, xp: usize) -> usize {
+        let cap = self.cap();
+        let mut level = 1;
+        while level < cap && self.min_xp(level + 1) <= xp {
+            level += 1;
+        }
+        level
+    }
+    pub fn xp_to_next_level(&self, xp: usize) -> usize {
+        let level = self.level(xp);
+        if level == self.cap() {
+            0
+        } else {
+            self.min_xp(level + 1) - xp
+        }
+    }
+}
+
+impl Default for XpCurve {
+    fn default() -> Self {
+        Self::Polynomial {
+            base: 100.0,
+            exponent: 2.0,
+            cap: 50,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_thresholds(curve: &XpCurve) {
+        assert_eq!(curve.level(0), 1);
+        for level in 2..=curve.cap() {
+            let x = curve.min_xp(level);
+            assert_eq!(curve.level(x), level);
+            assert_eq!(curve.level(x - 1), level - 1);
+            assert_eq!(curve.xp_to_next_level(x - 1), 1);
+        }
+        assert_eq!(curve.xp_to_next_level(usize::MAX), 0);
+    }
+
+    #[test]
+    fn classic() {
+        // The formula used before curves were configurable.
+        fn level(x: usize) -> usize {
+            (10.0 * (1.0 - (-(x as f64) / R).exp())).ceil().max(1.0) as usize
+        }
+        let curve = XpCurve::classic();
+        for x in (0..20000).step_by(7) {
+            assert_eq!(curve.level(x), level(x));
+        }
+        check_thresholds(&curve);
+    }
+
+    #[test]
+    fn polynomial() {
+        check_thresholds(&XpCurve::default());
+    }
+
+    #[test]
+    fn table() {
+        let curve = XpCurve::doubling();
+        assert_eq!(curve.level(99), 1);
+        assert_eq!(curve.level(100), 2);
+        assert_eq!(curve.level(799), 5);
+        assert_eq!(curve.level(12800), 10);
+        check_thresholds(&curve);
+    }
+}
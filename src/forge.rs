@@ -0,0 +1,211 @@
+use crate::{
+    equipment::*, material::*, multiset::MultiSet, player::Player, spell::Offense, utils::is_quit,
+};
+use regex::Regex;
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+/// Gold charged for each enchantment.
+pub const ENCHANT_GOLD: usize = 25;
+
+pub fn enchant_materials(element: Offense) -> MultiSet<Material> {
+    match element {
+        Offense::Stone => [(FairyDust, 1), (Bone, 2)],
+        Offense::Fire => [(FairyDust, 1), (VampireFang, 1)],
+    }
+    .into_iter()
+    .collect()
+}
+
+fn write_materials<T: std::fmt::Write>(f: &mut T, materials: &MultiSet<Material>) {
+    for (i, (material, count)) in materials.bag.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ").unwrap();
+        }
+        write!(f, "{count} {}", material.plural()).unwrap();
+    }
+}
+
+fn read_transaction<T: FromStr>(prompt: &str, gold: usize, n: usize) -> T {
+    let mut buf = String::with_capacity(1 << 7);
+    loop {
+        String::clear(&mut buf);
+        print!("(💰: {}) {} ", gold, prompt);
+        io::Write::flush(&mut io::stdout()).unwrap();
+
+        let stdin = io::stdin();
+        let mut handle = stdin.lock();
+        match handle.read_line(&mut buf) {
+            Ok(_) => {
+                let _ = crate::readline::clear_last_n_lines(1);
+            }
+            Err(e) => println!("Error in forge menu readline: {:#?}", e),
+        }
+        if let Ok(transaction) = buf.parse::<T>() {
+            let _ = crate::readline::clear_last_n_lines(n);
+            break transaction;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradeTransaction {
+    Upgrade(Gear),
+    Quit,
+}
+
+impl FromStr for UpgradeTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_UPGRADE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:upgrade|u)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.split_once(' ') {
+            if RE_UPGRADE.is_match(lhs) {
+                if let Ok(kind) = rhs.parse::<Gear>() {
+                    return Ok(Self::Upgrade(kind));
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(Self::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+/// Upgrades equipped gear from +0 up to `MAX_UPGRADE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Forge;
+
+impl Forge {
+    pub fn message(&self, equipment: &Equipment) -> String {
+        let mut s = String::with_capacity(1 << 10);
+        writeln!(
+            s,
+            "                                    | {} | {} | {}",
+            "chance".underline(),
+            "gold".underline(),
+            "materials".underline(),
+        )
+        .unwrap();
+        for piece in equipment.slots().into_iter().filter(|x| x.can_upgrade()) {
+            write!(
+                s,
+                "    {:<40} | {:>5}% | {:>4} | ",
+                format!("{}", piece),
+                piece.upgrade_chance(),
+                piece.upgrade_gold(),
+            )
+            .unwrap();
+            write_materials(&mut s, &piece.upgrade_materials());
+            s.push('\n');
+        }
+        s
+    }
+    pub fn visit(&self, player: &mut Player) -> bool {
+        let msg = self.message(&player.equipment);
+        println!("---- Visiting the forge... ----");
+        println!("{}", msg);
+        println!("e.g. `upgrade sword`; `q` to leave");
+        match read_transaction::<UpgradeTransaction>("🔨", player.gold, msg.lines().count() + 3) {
+            UpgradeTransaction::Upgrade(kind) => {
+                player.upgrade(kind);
+                true
+            }
+            UpgradeTransaction::Quit => false,
+        }
+    }
+    pub fn work(&self, player: &mut Player) {
+        while self.visit(player) {}
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnchantTransaction {
+    Enchant(Offense),
+    Quit,
+}
+
+impl FromStr for EnchantTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_ENCHANT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:enchant|en)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.split_once(' ') {
+            if RE_ENCHANT.is_match(lhs) {
+                if let Ok(element) = rhs.parse::<Offense>() {
+                    return Ok(Self::Enchant(element));
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(Self::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+/// Imbues the equipped weapon with an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Enchanter;
+
+impl Enchanter {
+    pub fn message(&self, equipment: &Equipment) -> String {
+        let mut s = String::with_capacity(1 << 10);
+        writeln!(s, "{}: {}", "Weapon".underline(), equipment.weapon()).unwrap();
+        for element in [Offense::Stone, Offense::Fire] {
+            write!(
+                s,
+                "    {:<24} | {:>4} gold | ",
+                format!("{}", element),
+                ENCHANT_GOLD
+            )
+            .unwrap();
+            write_materials(&mut s, &enchant_materials(element));
+            s.push('\n');
+        }
+        s
+    }
+    pub fn visit(&self, player: &mut Player) -> bool {
+        let msg = self.message(&player.equipment);
+        println!("---- Visiting the enchanter... ----");
+        println!("{}", msg);
+        println!("e.g. `enchant fire`; `q` to leave");
+        match read_transaction::<EnchantTransaction>("🔮", player.gold, msg.lines().count() + 3) {
+            EnchantTransaction::Enchant(element) => {
+                player.enchant(element);
+                true
+            }
+            EnchantTransaction::Quit => false,
+        }
+    }
+    pub fn work(&self, player: &mut Player) {
+        while self.visit(player) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "u sword".parse::<UpgradeTransaction>().unwrap(),
+            UpgradeTransaction::Upgrade(Sword)
+        );
+        assert_eq!(
+            "enchant f".parse::<EnchantTransaction>().unwrap(),
+            EnchantTransaction::Enchant(Offense::Fire)
+        );
+        assert!("u".parse::<UpgradeTransaction>().is_err());
+        assert!("enchant ice".parse::<EnchantTransaction>().is_err());
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{adventure::*, dungeon::Dungeon, maze::MazeGraph, player::*, town::*, trade::Merchant};
+use crate::{
+    adventure::*, bank::Banker, blacksmith::Blacksmith, class::class_menu, clock::*,
+    consumable::Setting, craft::RecipeBook, death::*, dungeon::Dungeon, forge::*, inn::Inn,
+    jeweller::Jeweller, market::Market, maze::MazeGraph, player::*, quest::QuestBoard, town::*,
+};
 use rand::Rng;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -24,48 +28,134 @@ pub fn game() {
     let mut rng = rand::thread_rng();
 
     let mut state = State::Town;
-    let mut player = Player::new();
+    let mut player = Player::new(class_menu());
+    player.rules = mode_menu().rules();
 
-    let mut merchant = Merchant::new();
+    let mut market = Market::new();
 
     let mut graph = MazeGraph::new_demo();
 
+    let mut board = QuestBoard::new(player.level(), graph.0.len());
+
+    let mut clock = Clock::new();
+    let mut day = clock.day();
+
     loop {
+        while day < clock.day() {
+            day += 1;
+            new_day(day, &mut player, &mut market, &mut graph);
+            board.refresh(player.level(), graph.0.len());
+        }
         if player.is_alive() {
             match state {
-                State::Town => match town_menu() {
+                State::Town => match town_menu(&clock) {
                     TownAction::Adventure => {
                         state = State::Adventure;
                     }
                     TownAction::Dungeon => {
                         state = State::Dungeon;
                     }
-                    TownAction::Sleep => {
-                        player.sleep();
+                    TownAction::Sleep => Inn.work(&mut player, &mut clock),
+                    TownAction::Trade if clock.is_night() => {
+                        println!("The shops are closed for the night.");
+                    }
+                    TownAction::Trade => market.trade(&mut player),
+                    TownAction::Blacksmith => Blacksmith.work(&mut player),
+                    TownAction::Craft => RecipeBook.work(&mut player),
+                    TownAction::Upgrade => Forge.work(&mut player),
+                    TownAction::Enchant => Enchanter.work(&mut player),
+                    TownAction::Jeweller => Jeweller.work(&mut player),
+                    TownAction::Bank => Banker.work(&mut player),
+                    TownAction::Quests => board.work(&mut player),
+                    TownAction::Inventory => {
+                        player.noncombat_inventory(Setting::Town);
                     }
-                    TownAction::Trade => merchant.trade(&mut player),
-                    TownAction::Inventory => player.noncombat_inventory(),
                     TownAction::Equipment => player.noncombat_equipment(),
-                    TownAction::Stats => println!("{}", player.attribute_message()),
+                    TownAction::Stats => player.view_stats(),
+                    TownAction::Skills => player.noncombat_skills(),
                 },
                 State::Dungeon => {
                     let n_monster: usize = rng.gen_range(1..5);
                     state = State::Town;
                     let mut dungeon = Dungeon::new(&mut player, n_monster);
                     dungeon.run();
+                    clock.advance(DUNGEON_MINUTES);
                 }
                 State::Adventure => {
-                    let mut adv = Adventure::new(&mut player, &mut graph);
+                    let mut adv = Adventure::new(&mut player, &mut graph, &mut clock);
                     adv.run();
 
                     state = State::Town;
                 }
             }
+        } else if player.rules.is_hardcore() {
+            println!("---- Your adventure has come to an end. ----");
+            if let Err(e) = record(&player) {
+                println!("Error recording hall of fame: {:#?}", e);
+            }
+            if let Ok(s) = hall_of_fame() {
+                println!("{}", s);
+            }
+            break;
         } else {
             state = State::Town;
             println!("Another adventurer found your body and carried it to the town.");
             println!("You are now being revived...");
+            player.apply_death_penalty();
             player.revive();
         }
     }
 }
+
+/// Time moves on: merchants restock, monsters return to their lairs,
+/// savings earn interest and the `PhoenixAmulet` recharges.
+fn new_day(day: usize, player: &mut Player, market: &mut Market, graph: &mut MazeGraph) {
+    println!("---- Day {day} dawns. ----");
+    market.restock(player.level());
+    graph.respawn_monsters();
+    player.phoenix_spent = false;
+    let interest = player.bank.accrue_interest();
+    if interest > 0 {
+        println!("Your savings earned {interest} gold in interest.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{class::Class, inn::Room};
+
+    #[test]
+    fn sleep_creates_no_gold() {
+        let mut player = Player::new(Class::Warrior);
+        let mut market = Market::new();
+        let mut graph = MazeGraph::new_demo();
+        let mut clock = Clock::new();
+        let mut day = clock.day();
+        player.bank.gold = 1000;
+        let total = player.gold + player.bank.gold;
+        for _ in 0..10 {
+            Inn.rent(&mut player, &mut clock, Room::Stable);
+            while day < clock.day() {
+                day += 1;
+                new_day(day, &mut player, &mut market, &mut graph);
+            }
+        }
+        assert_eq!(day, 1);
+        assert_eq!(player.gold + player.bank.gold, total);
+    }
+
+    #[test]
+    fn phoenix_recharges_daily() {
+        let mut player = Player::new(Class::Warrior);
+        let mut market = Market::new();
+        let mut graph = MazeGraph::new_demo();
+        let mut clock = Clock::new();
+        player.phoenix_spent = true;
+        clock.advance(TIRED_MINUTES);
+        assert!(Inn.rent(&mut player, &mut clock, Room::Stable));
+        assert!(player.phoenix_spent);
+        new_day(clock.day(), &mut player, &mut market, &mut graph);
+        assert!(!player.phoenix_spent);
+    }
+}
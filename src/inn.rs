@@ -0,0 +1,187 @@
+use crate::{clock::*, player::Player, utils::is_quit};
+use regex::Regex;
+use std::{
+    fmt,
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::{Paint, Painted};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Room {
+    Stable,
+    Common,
+    Private,
+    Suite,
+}
+use Room::*;
+
+impl Room {
+    pub(crate) const fn all() -> [Room; 4] {
+        [Stable, Common, Private, Suite]
+    }
+    pub const fn cost(&self) -> usize {
+        match self {
+            Stable => 0,
+            Common => 10,
+            Private => 30,
+            Suite => 80,
+        }
+    }
+    /// Percentage of maximum HP and MP restored by a night's sleep.
+    pub const fn restore_pct(&self) -> i64 {
+        match self {
+            Stable => 50,
+            Common | Private | Suite => 100,
+        }
+    }
+    /// Bonus experience, in percent, until the next night's sleep.
+    pub const fn rested_xp_pct(&self) -> usize {
+        match self {
+            Stable | Common => 0,
+            Private => 10,
+            Suite => 20,
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Stable => "Restore half of HP and MP",
+            Common => "Restore all HP and MP",
+            Private => "Restore all; well rested (+10% experience)",
+            Suite => "Restore all; very well rested (+20% experience)",
+        }
+    }
+    pub(crate) fn print_menu_item(&self) {
+        const GOLD: Painted<&'static str> = Painted::new("gold").bold().yellow();
+        println!(
+            "    {:<30} | {:>3} {} | {}",
+            format!("{}", self),
+            self.cost(),
+            GOLD,
+            self.description(),
+        );
+    }
+}
+
+impl fmt::Display for Room {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stable => write!(f, "{}table", "S".bold().underline()),
+            Common => write!(f, "{}ommon room", "C".bold().underline()),
+            Private => write!(f, "{}rivate room", "P".bold().underline()),
+            Suite => write!(f, "Sui{}e", "t".bold().underline()),
+        }
+    }
+}
+
+impl FromStr for Room {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        static RE_STABLE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:stable|s)$").unwrap());
+        static RE_COMMON: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:common(?: room)?|c)$").unwrap());
+        static RE_PRIVATE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:private(?: room)?|p)$").unwrap());
+        static RE_SUITE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:suite|t)$").unwrap());
+
+        if RE_STABLE.is_match(s) {
+            Ok(Stable)
+        } else if RE_COMMON.is_match(s) {
+            Ok(Common)
+        } else if RE_PRIVATE.is_match(s) {
+            Ok(Private)
+        } else if RE_SUITE.is_match(s) {
+            Ok(Suite)
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
+/// Rents out rooms for the night.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Inn;
+
+impl Inn {
+    /// Returns `None` if the player leaves the inn.
+    pub fn menu(&self, gold: usize, clock: &Clock) -> Option<Room> {
+        let mut buf = String::with_capacity(1 << 7);
+        println!("==== Visiting the inn... ({}) ====", clock);
+        for room in Room::all() {
+            room.print_menu_item();
+        }
+        println!("`q` to leave");
+        let n = Room::all().len() + 2;
+        loop {
+            String::clear(&mut buf);
+            print!("(💰: {}) 🛏️ ", gold);
+            io::Write::flush(&mut io::stdout()).unwrap();
+
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            match handle.read_line(&mut buf) {
+                Ok(_) => {
+                    let _ = crate::readline::clear_last_n_lines(1);
+                }
+                Err(e) => println!("Error in inn menu readline: {:#?}", e),
+            }
+            let s = buf.trim();
+            if is_quit(s) {
+                let _ = crate::readline::clear_last_n_lines(n);
+                return None;
+            } else if let Ok(room) = s.parse::<Room>() {
+                let _ = crate::readline::clear_last_n_lines(n);
+                return Some(room);
+            }
+        }
+    }
+    /// Returns `true` if the player spent the night.
+    pub fn rent(&self, player: &mut Player, clock: &mut Clock, room: Room) -> bool {
+        if !clock.is_tired() {
+            println!("Innkeeper rejected transaction: you are not tired yet!");
+            return false;
+        }
+        if player.gold < room.cost() {
+            println!("Innkeeper rejected transaction: insufficient gold!");
+            return false;
+        }
+        player.gold -= room.cost();
+        player.sleep(room);
+        clock.sleep();
+        true
+    }
+    pub fn work(&self, player: &mut Player, clock: &mut Clock) {
+        while let Some(room) = self.menu(player.gold, clock) {
+            if self.rent(player, clock, room) {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rent() {
+        let mut player = Player::new(crate::class::Class::Warrior);
+        let mut clock = Clock::new();
+        player.gold = Private.cost();
+        player.receive_damage(80);
+        assert!(!Inn.rent(&mut player, &mut clock, Private));
+        clock.advance(TIRED_MINUTES);
+        assert!(!Inn.rent(&mut player, &mut clock, Suite));
+        assert_eq!(clock.day(), 1);
+        assert!(Inn.rent(&mut player, &mut clock, Private));
+        assert_eq!((player.gold, clock.day()), (0, 2));
+        assert_eq!(player.hp.current, player.hp.max);
+        // Small enough not to level up, which would prompt for attributes
+        assert_eq!(player.gain_xp(10), 10 + 10 * Private.rested_xp_pct() / 100);
+    }
+}
@@ -130,7 +130,39 @@ impl Inventory {
     pub fn n_available(&self, kind: &Consumable) -> usize {
         self.0.n_available(kind)
     }
-    pub(crate) fn fmt_imp<T: fmt::Write>(&self, f: &mut T, field2: &'static str) -> fmt::Result {
+    pub fn weight(&self) -> usize {
+        self.0
+            .bag
+            .iter()
+            .map(|(item, count)| item.weight() * count)
+            .sum()
+    }
+    /// Remove `pct` percent of each item (rounded down), returning the removed items.
+    pub fn split_off_pct(&mut self, pct: usize) -> Self {
+        let removed: Vec<_> = self
+            .0
+            .bag
+            .iter()
+            .map(|(item, count)| (*item, count * pct.min(100) / 100))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        for (item, count) in removed.iter() {
+            self.drop_multiple(*item, *count);
+        }
+        removed.into_iter().collect()
+    }
+    pub fn append(&mut self, other: Self) {
+        for (item, count) in other.0.bag.into_iter() {
+            self.push_multiple(item, count);
+        }
+    }
+    /// List the items, with `price` giving the gold shown for each one.
+    pub(crate) fn fmt_imp<T: fmt::Write>(
+        &self,
+        f: &mut T,
+        field2: &'static str,
+        price: impl Fn(&Consumable) -> usize,
+    ) -> fmt::Result {
         if self.is_empty() {
             writeln!(f, "Inventory is empty!")?;
         } else {
@@ -149,7 +181,7 @@ impl Inventory {
                     "    {:<30} | {:^9} | {:>2} {} | {:<30}",
                     format!("{}", item),
                     count,
-                    item.cost(),
+                    price(item),
                     GOLD,
                     item.description(),
                 )?;
@@ -169,6 +201,6 @@ impl FromIterator<(Consumable, usize)> for Inventory {
 
 impl fmt::Display for Inventory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.fmt_imp(f, "value")
+        self.fmt_imp(f, "value", Consumable::cost)
     }
 }
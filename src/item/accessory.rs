@@ -0,0 +1,160 @@
+use rand::Rng;
+use regex::Regex;
+use std::{fmt, str::FromStr, sync::LazyLock};
+use yansi::Paint;
+
+/// Experience bonus, in percent, granted by the `ScholarsRing`.
+pub const SCHOLAR_XP_PCT: usize = 10;
+/// MP restored by the `ManaRing` with each step through a maze.
+pub const MANA_RING_MP: i64 = 3;
+/// Percentage of maximum HP restored by the `PhoenixAmulet`.
+pub const PHOENIX_HP_PCT: i64 = 50;
+
+/// Where an accessory is worn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AccessorySlot {
+    Ring,
+    Amulet,
+}
+
+impl FromStr for AccessorySlot {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_RING: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^rings?$").unwrap());
+        static RE_AMULET: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^amulets?$").unwrap());
+
+        let s = s.trim();
+        if RE_RING.is_match(s) {
+            Ok(Self::Ring)
+        } else if RE_AMULET.is_match(s) {
+            Ok(Self::Amulet)
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
+impl fmt::Display for AccessorySlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ring => write!(f, "Ring"),
+            Self::Amulet => write!(f, "Amulet"),
+        }
+    }
+}
+
+/// A ring or amulet whose effect lies outside of the usual stats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Accessory {
+    ScholarsRing,
+    ManaRing,
+    SerpentRing,
+    PhoenixAmulet,
+    SeersAmulet,
+}
+pub use Accessory::*;
+
+impl Accessory {
+    pub(crate) const fn total_variants() -> usize {
+        5
+    }
+    pub(crate) const fn all() -> [Accessory; 5] {
+        [
+            ScholarsRing,
+            ManaRing,
+            SerpentRing,
+            PhoenixAmulet,
+            SeersAmulet,
+        ]
+    }
+    pub const fn slot(&self) -> AccessorySlot {
+        match self {
+            ScholarsRing | ManaRing | SerpentRing => AccessorySlot::Ring,
+            PhoenixAmulet | SeersAmulet => AccessorySlot::Amulet,
+        }
+    }
+    pub const fn cost(&self) -> usize {
+        match self {
+            ScholarsRing => 120,
+            ManaRing => 80,
+            SerpentRing => 60,
+            PhoenixAmulet => 250,
+            SeersAmulet => 90,
+        }
+    }
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ScholarsRing => "Scholar's Ring",
+            ManaRing => "Mana Ring",
+            SerpentRing => "Serpent Ring",
+            PhoenixAmulet => "Phoenix Amulet",
+            SeersAmulet => "Seer's Amulet",
+        }
+    }
+    pub fn description(&self) -> String {
+        match self {
+            ScholarsRing => format!("+{SCHOLAR_XP_PCT}% experience"),
+            ManaRing => format!("+{MANA_RING_MP} MP with each step"),
+            SerpentRing => "Immunity to poison".to_string(),
+            PhoenixAmulet => "Revive once per day".to_string(),
+            SeersAmulet => "See monsters before engaging".to_string(),
+        }
+    }
+    pub fn gen<T: Rng>(rng: &mut T) -> Self {
+        Self::all()[rng.gen_range(0..Self::total_variants())]
+    }
+}
+
+impl fmt::Display for Accessory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name().rgb(0x40, 0xe0, 0xd0))
+    }
+}
+
+impl FromStr for Accessory {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_SCHOLAR: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^scholar(?:'?s)?(?: ring)?$").unwrap());
+        static RE_MANA: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^mana(?: ring)?$").unwrap());
+        static RE_SERPENT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^serpent(?: ring)?$").unwrap());
+        static RE_PHOENIX: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^phoenix(?: amulet)?$").unwrap());
+        static RE_SEER: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^seer(?:'?s)?(?: amulet)?$").unwrap());
+
+        let s = s.trim();
+        if RE_SCHOLAR.is_match(s) {
+            Ok(ScholarsRing)
+        } else if RE_MANA.is_match(s) {
+            Ok(ManaRing)
+        } else if RE_SERPENT.is_match(s) {
+            Ok(SerpentRing)
+        } else if RE_PHOENIX.is_match(s) {
+            Ok(PhoenixAmulet)
+        } else if RE_SEER.is_match(s) {
+            Ok(SeersAmulet)
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!("Scholar's Ring".parse::<Accessory>().unwrap(), ScholarsRing);
+        assert_eq!("mana".parse::<Accessory>().unwrap(), ManaRing);
+        assert_eq!("seers amulet".parse::<Accessory>().unwrap(), SeersAmulet);
+        assert!("ring".parse::<Accessory>().is_err());
+        assert_eq!(
+            "amulet".parse::<AccessorySlot>().unwrap(),
+            AccessorySlot::Amulet
+        );
+    }
+}
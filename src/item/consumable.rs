@@ -15,25 +15,110 @@ pub enum Consumable {
     ManaPotion,
     /// Restores 10 HP and 10 MP
     Food,
+    /// Cures poison
+    Antidote,
+    /// Restores all HP, MP and TP
+    Elixir,
+    /// Restores 50 TP
+    TpTonic,
+    /// Guarantees escape from combat
+    SmokeBomb,
+    /// Deals 60 damage to the monster
+    Bomb,
+    /// Warps from the open world to town
+    ReturnScroll,
 }
 pub use Consumable::*;
 
+/// Where a consumable may be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Usability {
+    CombatOnly,
+    MapOnly,
+    Anywhere,
+}
+
+/// Where the player currently is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Setting {
+    Combat,
+    Map,
+    Town,
+}
+
+impl Usability {
+    pub const fn allows(&self, setting: Setting) -> bool {
+        matches!(
+            (self, setting),
+            (Usability::Anywhere, _)
+                | (Usability::CombatOnly, Setting::Combat)
+                | (Usability::MapOnly, Setting::Map)
+        )
+    }
+}
+
+impl fmt::Display for Usability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Usability::CombatOnly => write!(f, "in combat"),
+            Usability::MapOnly => write!(f, "on the map"),
+            Usability::Anywhere => write!(f, "anywhere"),
+        }
+    }
+}
+
+/// What happens, beyond restoration, when a consumable is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemEffect {
+    Nothing,
+    Escape,
+    Damage(i64),
+    Warp,
+}
+
 impl Consumable {
     pub(crate) const fn total_variants() -> usize {
-        3
+        9
     }
     pub const fn healing(&self) -> i64 {
         match self {
             HealthPotion => 50,
-            ManaPotion => 0,
             Food => 10,
+            _ => 0,
         }
     }
     pub const fn mana_restore(&self) -> i64 {
         match self {
-            HealthPotion => 0,
             ManaPotion => 50,
             Food => 10,
+            _ => 0,
+        }
+    }
+    pub const fn tp_restore(&self) -> i64 {
+        match self {
+            TpTonic => 50,
+            _ => 0,
+        }
+    }
+    pub const fn damage(&self) -> i64 {
+        match self {
+            Bomb => 60,
+            _ => 0,
+        }
+    }
+    pub const fn usability(&self) -> Usability {
+        match self {
+            SmokeBomb | Bomb => Usability::CombatOnly,
+            ReturnScroll => Usability::MapOnly,
+            _ => Usability::Anywhere,
+        }
+    }
+    pub const fn effect(&self) -> ItemEffect {
+        match self {
+            SmokeBomb => ItemEffect::Escape,
+            Bomb => ItemEffect::Damage(self.damage()),
+            ReturnScroll => ItemEffect::Warp,
+            _ => ItemEffect::Nothing,
         }
     }
     pub(crate) fn description_imp(&self) -> String {
@@ -47,6 +132,17 @@ impl Consumable {
                 self.mana_restore(),
                 Mana::MP,
             ),
+            Antidote => "cures poison".to_string(),
+            Elixir => format!(
+                "restores all {}, {} and {}",
+                Health::HP,
+                Mana::MP,
+                Technical::TP
+            ),
+            TpTonic => format!("restores {} {}", self.tp_restore(), Technical::TP),
+            SmokeBomb => "guarantees escape (combat only)".to_string(),
+            Bomb => format!("deals {} damage (combat only)", self.damage()),
+            ReturnScroll => "warps to town (map only)".to_string(),
         }
     }
 
@@ -54,11 +150,23 @@ impl Consumable {
         static HEALTH_POTION: LazyLock<String> = LazyLock::new(|| HealthPotion.description_imp());
         static MANA_POTION: LazyLock<String> = LazyLock::new(|| ManaPotion.description_imp());
         static FOOD: LazyLock<String> = LazyLock::new(|| Food.description_imp());
+        static ANTIDOTE: LazyLock<String> = LazyLock::new(|| Antidote.description_imp());
+        static ELIXIR: LazyLock<String> = LazyLock::new(|| Elixir.description_imp());
+        static TP_TONIC: LazyLock<String> = LazyLock::new(|| TpTonic.description_imp());
+        static SMOKE_BOMB: LazyLock<String> = LazyLock::new(|| SmokeBomb.description_imp());
+        static BOMB: LazyLock<String> = LazyLock::new(|| Bomb.description_imp());
+        static RETURN_SCROLL: LazyLock<String> = LazyLock::new(|| ReturnScroll.description_imp());
 
         match self {
-            HealthPotion => &*HEALTH_POTION,
-            ManaPotion => &*MANA_POTION,
-            Food => &*FOOD,
+            HealthPotion => &HEALTH_POTION,
+            ManaPotion => &MANA_POTION,
+            Food => &FOOD,
+            Antidote => &ANTIDOTE,
+            Elixir => &ELIXIR,
+            TpTonic => &TP_TONIC,
+            SmokeBomb => &SMOKE_BOMB,
+            Bomb => &BOMB,
+            ReturnScroll => &RETURN_SCROLL,
         }
     }
 
@@ -73,6 +181,9 @@ impl Consumable {
                 self.mana_restore().magenta(),
                 Mana::MP,
             ),
+            TpTonic => format!("restores {} {}", self.tp_restore().magenta(), Technical::TP),
+            Bomb => format!("deals {} damage", self.damage().magenta()),
+            _ => self.description_imp(),
         }
     }
 
@@ -82,11 +193,16 @@ impl Consumable {
         static MANA_POTION: LazyLock<String> =
             LazyLock::new(|| ManaPotion.combat_description_imp());
         static FOOD: LazyLock<String> = LazyLock::new(|| Food.combat_description_imp());
+        static TP_TONIC: LazyLock<String> = LazyLock::new(|| TpTonic.combat_description_imp());
+        static BOMB: LazyLock<String> = LazyLock::new(|| Bomb.combat_description_imp());
 
         match self {
-            HealthPotion => &*HEALTH_POTION,
-            ManaPotion => &*MANA_POTION,
-            Food => &*FOOD,
+            HealthPotion => &HEALTH_POTION,
+            ManaPotion => &MANA_POTION,
+            Food => &FOOD,
+            TpTonic => &TP_TONIC,
+            Bomb => &BOMB,
+            _ => self.description(),
         }
     }
     pub const fn cost(&self) -> usize {
@@ -94,6 +210,28 @@ impl Consumable {
             HealthPotion => 2,
             ManaPotion => 3,
             Food => 1,
+            Antidote => 2,
+            Elixir => 15,
+            TpTonic => 3,
+            SmokeBomb => 4,
+            Bomb => 5,
+            ReturnScroll => 5,
+        }
+    }
+    pub const fn weight(&self) -> usize {
+        match self {
+            Bomb => 2,
+            ReturnScroll => 0,
+            _ => 1,
+        }
+    }
+    /// Relative frequency with which the item is found as loot.
+    pub(crate) const fn rarity_weight(&self) -> usize {
+        match self {
+            HealthPotion | ManaPotion | Food => 6,
+            Antidote | TpTonic => 3,
+            SmokeBomb | Bomb | ReturnScroll => 2,
+            Elixir => 1,
         }
     }
 
@@ -101,16 +239,38 @@ impl Consumable {
         const HEALTHPOTION: u8 = HealthPotion as u8;
         const MANAPOTION: u8 = ManaPotion as u8;
         const FOOD: u8 = Food as u8;
+        const ANTIDOTE: u8 = Antidote as u8;
+        const ELIXIR: u8 = Elixir as u8;
+        const TPTONIC: u8 = TpTonic as u8;
+        const SMOKEBOMB: u8 = SmokeBomb as u8;
+        const BOMB: u8 = Bomb as u8;
+        const RETURNSCROLL: u8 = ReturnScroll as u8;
 
         match i {
             HEALTHPOTION => HealthPotion,
             MANAPOTION => ManaPotion,
             FOOD => Food,
+            ANTIDOTE => Antidote,
+            ELIXIR => Elixir,
+            TPTONIC => TpTonic,
+            SMOKEBOMB => SmokeBomb,
+            BOMB => Bomb,
+            RETURNSCROLL => ReturnScroll,
             _ => panic!(),
         }
     }
     pub fn gen<T: Rng>(rng: &mut T) -> Self {
-        Self::from_index(rng.gen_range(0u8..3u8))
+        let n = Self::total_variants() as u8;
+        let total: usize = (0..n).map(|i| Self::from_index(i).rarity_weight()).sum();
+        let mut x = rng.gen_range(0..total);
+        for i in 0..n {
+            let item = Self::from_index(i);
+            if x < item.rarity_weight() {
+                return item;
+            }
+            x -= item.rarity_weight();
+        }
+        unreachable!()
     }
     pub fn rand() -> Self {
         let mut rng = rand::thread_rng();
@@ -128,6 +288,17 @@ impl FromStr for Consumable {
         static RE_MP: LazyLock<Regex> =
             LazyLock::new(|| Regex::new(r"(?i)^(?:mp|mana\s+potion)$").unwrap());
         static RE_FOOD: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:food|f)$").unwrap());
+        static RE_ANTIDOTE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:antidote|ant)$").unwrap());
+        static RE_ELIXIR: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:elixir|el)$").unwrap());
+        static RE_TP: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^(?:tp|tonic|tp\s+tonic)$").unwrap());
+        static RE_SMOKE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^(?:smoke|smoke\s+bomb)$").unwrap());
+        static RE_BOMB: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^bomb$").unwrap());
+        static RE_SCROLL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^(?:scroll|return\s+scroll)$").unwrap());
 
         if RE_HP.is_match(s) {
             Ok(HealthPotion)
@@ -135,6 +306,18 @@ impl FromStr for Consumable {
             Ok(ManaPotion)
         } else if RE_FOOD.is_match(s) {
             Ok(Food)
+        } else if RE_ANTIDOTE.is_match(s) {
+            Ok(Antidote)
+        } else if RE_ELIXIR.is_match(s) {
+            Ok(Elixir)
+        } else if RE_TP.is_match(s) {
+            Ok(TpTonic)
+        } else if RE_SMOKE.is_match(s) {
+            Ok(SmokeBomb)
+        } else if RE_BOMB.is_match(s) {
+            Ok(Bomb)
+        } else if RE_SCROLL.is_match(s) {
+            Ok(ReturnScroll)
         } else {
             Err(s.to_string())
         }
@@ -147,6 +330,12 @@ impl fmt::Display for Consumable {
             HealthPotion => write!(f, "{}", "Health potion".cyan()),
             ManaPotion => write!(f, "{}", "Mana potion".cyan()),
             Food => write!(f, "{}", "Food".cyan()),
+            Antidote => write!(f, "{}", "Antidote".cyan()),
+            Elixir => write!(f, "{}", "Elixir".cyan()),
+            TpTonic => write!(f, "{}", "TP tonic".cyan()),
+            SmokeBomb => write!(f, "{}", "Smoke bomb".cyan()),
+            Bomb => write!(f, "{}", "Bomb".cyan()),
+            ReturnScroll => write!(f, "{}", "Return scroll".cyan()),
         }
     }
 }
@@ -167,6 +356,12 @@ mod tests {
         test_eq!(HealthPotion ; "hp", "HP", "health potion", "Health potion", "health    potion");
         test_eq!(ManaPotion ; "mp", "MP", "mana potion", "Mana potion", "mana    potion");
         test_eq!(Food ; "f", "F", "foOd", "food");
+        test_eq!(Antidote ; "ant", "antidote", "Antidote");
+        test_eq!(Elixir ; "el", "elixir", "ELIXIR");
+        test_eq!(TpTonic ; "tp", "tonic", "tp tonic", "TP   Tonic");
+        test_eq!(SmokeBomb ; "smoke", "smoke bomb", "Smoke Bomb");
+        test_eq!(Bomb ; "bomb", "BOMB");
+        test_eq!(ReturnScroll ; "scroll", "return scroll", "Return  Scroll");
 
         macro_rules! test_err {
             ($($s:literal),+) => {
@@ -175,6 +370,17 @@ mod tests {
                 )+
             }
         }
-        test_err!("a", "c", "bu", "sel", "qui", "1234");
+        test_err!("a", "c", "bu", "sel", "qui", "1234", "bombs");
+    }
+
+    #[test]
+    fn usability() {
+        assert!(HealthPotion.usability().allows(Setting::Combat));
+        assert!(HealthPotion.usability().allows(Setting::Town));
+        assert!(Bomb.usability().allows(Setting::Combat));
+        assert!(!Bomb.usability().allows(Setting::Map));
+        assert!(ReturnScroll.usability().allows(Setting::Map));
+        assert!(!ReturnScroll.usability().allows(Setting::Combat));
+        assert!(!ReturnScroll.usability().allows(Setting::Town));
     }
 }
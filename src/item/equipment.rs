@@ -1,9 +1,13 @@
+use crate::{accessory::*, material::*, multiset::MultiSet, spell::Offense};
+use rand::Rng;
 use regex::Regex;
 use std::fmt;
 use std::hash::Hash;
+use std::mem;
+use std::ops;
 use std::str::FromStr;
 use std::sync::LazyLock;
-use yansi::{Paint, Painted};
+use yansi::{Color, Paint, Painted};
 
 // #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 // pub enum Weapon {
@@ -124,93 +128,230 @@ use yansi::{Paint, Painted};
 // }
 #[derive(Debug, Clone, PartialEq)]
 pub struct Equipment {
-    weapon: Gear,
-    head: Gear,
-    chest: Gear,
-    leg: Gear,
-    hand: Gear,
+    weapon: Piece,
+    /// A shield or a second one-handed weapon; empty when wielding a
+    /// two-handed weapon
+    offhand: Piece,
+    head: Piece,
+    chest: Piece,
+    leg: Piece,
+    hand: Piece,
+    ring: Option<Accessory>,
+    amulet: Option<Accessory>,
 }
 pub use Gear::*;
 impl Equipment {
-    pub const fn strength(&self) -> i64 {
-        self.weapon.strength()
-            + self.head.strength()
-            + self.chest.strength()
-            + self.leg.strength()
-            + self.hand.strength()
+    pub(crate) fn slots(&self) -> [&Piece; 6] {
+        [
+            &self.weapon,
+            &self.offhand,
+            &self.head,
+            &self.chest,
+            &self.leg,
+            &self.hand,
+        ]
     }
-    pub const fn intellect(&self) -> i64 {
-        self.weapon.intellect()
-            + self.head.intellect()
-            + self.chest.intellect()
-            + self.leg.intellect()
-            + self.hand.intellect()
+    fn slots_mut(&mut self) -> [&mut Piece; 6] {
+        [
+            &mut self.weapon,
+            &mut self.offhand,
+            &mut self.head,
+            &mut self.chest,
+            &mut self.leg,
+            &mut self.hand,
+        ]
     }
-    pub const fn armor(&self) -> i64 {
-        self.weapon.armor()
-            + self.head.armor()
-            + self.chest.armor()
-            + self.leg.armor()
-            + self.hand.armor()
-    }
-    pub fn equip(&mut self, item: Gear) -> Gear {
-        match item {
-            Fist | Sword | Axe | Wand | Staff => {
-                let old = self.weapon;
-                self.weapon = item;
-                old
-            }
-            Helmet | Hat => {
-                let old = self.head;
-                self.head = item;
-                old
-            }
-            Breastplate | Robe => {
-                let old = self.chest;
-                self.chest = item;
-                old
-            }
-            Greaves | Breeches => {
-                let old = self.leg;
-                self.leg = item;
-                old
-            }
-            Gauntlet | Glove => {
-                let old = self.hand;
-                self.hand = item;
-                old
+    pub fn strength(&self) -> i64 {
+        self.slots().iter().map(|x| x.strength()).sum::<i64>() + self.set_bonus().strength
+    }
+    pub fn intellect(&self) -> i64 {
+        self.slots().iter().map(|x| x.intellect()).sum::<i64>() + self.set_bonus().intellect
+    }
+    pub fn armor(&self) -> i64 {
+        self.slots().iter().map(|x| x.armor()).sum::<i64>() + self.set_bonus().armor
+    }
+    pub fn max_hp(&self) -> i64 {
+        self.slots().iter().map(|x| x.max_hp()).sum::<i64>() + self.set_bonus().max_hp
+    }
+    /// Number of intact pieces of `set` which are worn.
+    pub fn set_count(&self, set: GearSet) -> usize {
+        self.slots()
+            .iter()
+            .filter(|x| !x.is_broken() && set.pieces().contains(&x.kind))
+            .count()
+    }
+    pub fn set_bonus(&self) -> SetBonus {
+        GearSet::all()
+            .into_iter()
+            .flat_map(|set| set.active_tiers(self.set_count(set)))
+            .fold(SetBonus::default(), |acc, x| acc + x)
+    }
+    pub fn fire_damage(&self) -> i64 {
+        self.slots().iter().map(|x| x.fire_damage()).sum()
+    }
+    pub fn lifesteal(&self) -> i64 {
+        self.slots()
+            .iter()
+            .map(|x| x.lifesteal())
+            .sum::<i64>()
+            .min(50)
+    }
+    pub fn weapon(&self) -> &Piece {
+        &self.weapon
+    }
+    pub fn offhand(&self) -> &Piece {
+        &self.offhand
+    }
+    /// Percent chance to block a melee attack outright.
+    pub fn block_chance(&self) -> i64 {
+        self.offhand.block_chance()
+    }
+    /// The equipped piece of the given kind, preferring the main hand.
+    pub fn get_mut(&mut self, kind: Gear) -> Option<&mut Piece> {
+        self.slots_mut().into_iter().find(|x| x.kind == kind)
+    }
+    /// Returns the weapon's kind if it just broke.
+    pub fn wear_weapon(&mut self) -> Option<Gear> {
+        self.weapon.wear(1).then_some(self.weapon.kind)
+    }
+    /// Wear the armor by the `damage` taken through it.
+    /// Returns the kinds of the armor pieces which just broke.
+    pub fn wear_armor(&mut self, damage: usize) -> Vec<Gear> {
+        let amount = damage.div_ceil(DAMAGE_PER_WEAR);
+        [
+            &mut self.head,
+            &mut self.chest,
+            &mut self.leg,
+            &mut self.hand,
+        ]
+        .into_iter()
+        .filter_map(|x| x.wear(amount).then_some(x.kind))
+        .collect()
+    }
+    /// Returns the shield's kind if it just broke.
+    pub fn wear_shield(&mut self) -> Option<Gear> {
+        (self.offhand.kind.is_shield() && self.offhand.wear(1)).then_some(self.offhand.kind)
+    }
+    /// Pieces in need of repair, optionally restricted to one kind.
+    pub(crate) fn damaged(&self, kind: Option<Gear>) -> impl Iterator<Item = &Piece> {
+        self.slots()
+            .into_iter()
+            .filter(move |x| x.is_damaged() && kind.is_none_or(|kind| x.kind == kind))
+    }
+    pub fn repair(&mut self, kind: Option<Gear>) {
+        for slot in self.slots_mut() {
+            if kind.is_none_or(|kind| slot.kind == kind) {
+                slot.repair();
             }
-            Bare => Bare,
         }
     }
-    pub fn unequip(&mut self, item: Gear) -> Gear {
-        match item {
-            Fist | Sword | Axe | Wand | Staff => {
-                let old = self.weapon;
-                self.weapon = Fist;
-                old
-            }
-            Helmet | Hat => {
-                let old = self.head;
-                self.head = Bare;
-                old
-            }
-            Breastplate | Robe => {
-                let old = self.chest;
-                self.chest = Bare;
-                old
-            }
-            Greaves | Breeches => {
-                let old = self.leg;
-                self.leg = Bare;
-                old
-            }
-            Gauntlet | Glove => {
-                let old = self.hand;
-                self.hand = Bare;
-                old
-            }
-            Bare => Bare,
+    pub fn stats(&self) -> Stats {
+        Stats {
+            strength: self.strength(),
+            intellect: self.intellect(),
+            armor: self.armor(),
+            max_hp: self.max_hp(),
+            fire_damage: self.fire_damage(),
+            lifesteal: self.lifesteal(),
+        }
+    }
+    /// Preview equipping `item`, in the off-hand if `offhand`: the pieces it
+    /// displaces and the change in stats.
+    pub fn compare(&self, item: &Piece, offhand: bool) -> (Vec<Piece>, Stats) {
+        let mut after = self.clone();
+        let old = if offhand {
+            after.equip_offhand(item.clone())
+        } else {
+            after.equip(item.clone())
+        };
+        (old, after.stats() - self.stats())
+    }
+    /// Returns the pieces displaced by `item`, which may be both hands.
+    /// Weapons go to the main hand, and shields to the off-hand.
+    pub fn equip(&mut self, item: Piece) -> Vec<Piece> {
+        let kind = item.kind;
+        let mut old = Vec::with_capacity(2);
+        if kind.is_two_handed() {
+            old.push(mem::replace(&mut self.weapon, item));
+            old.push(mem::replace(&mut self.offhand, Piece::from(Bare)));
+        } else if kind.is_shield() {
+            return self.equip_offhand(item);
+        } else {
+            let slot = match kind {
+                Fist | Sword | Wand | Axe | Staff => &mut self.weapon,
+                Helmet | Hat => &mut self.head,
+                Breastplate | Robe => &mut self.chest,
+                Greaves | Breeches => &mut self.leg,
+                Gauntlet | Glove => &mut self.hand,
+                Shield | Bare => return vec![item],
+            };
+            old.push(mem::replace(slot, item));
+        }
+        old.retain(|x| !matches!(x.kind, Fist | Bare));
+        old
+    }
+    /// Returns the pieces displaced by holding `item` in the off-hand, which
+    /// takes a shield or a one-handed weapon; anything else is handed back.
+    pub fn equip_offhand(&mut self, item: Piece) -> Vec<Piece> {
+        let kind = item.kind;
+        if !kind.is_shield() && !item.is_one_handed() {
+            return vec![item];
+        }
+        let mut old = Vec::with_capacity(2);
+        if self.weapon.kind.is_two_handed() {
+            old.push(mem::replace(&mut self.weapon, Piece::from(Fist)));
+        }
+        old.push(mem::replace(&mut self.offhand, item));
+        old.retain(|x| !matches!(x.kind, Fist | Bare));
+        old
+    }
+    pub fn accessory(&self, slot: AccessorySlot) -> Option<Accessory> {
+        match slot {
+            AccessorySlot::Ring => self.ring,
+            AccessorySlot::Amulet => self.amulet,
+        }
+    }
+    pub fn wears(&self, accessory: Accessory) -> bool {
+        self.accessory(accessory.slot()) == Some(accessory)
+    }
+    /// Returns the accessory which previously occupied the slot.
+    pub fn equip_accessory(&mut self, accessory: Accessory) -> Option<Accessory> {
+        let slot = match accessory.slot() {
+            AccessorySlot::Ring => &mut self.ring,
+            AccessorySlot::Amulet => &mut self.amulet,
+        };
+        slot.replace(accessory)
+    }
+    pub fn unequip_accessory(&mut self, slot: AccessorySlot) -> Option<Accessory> {
+        match slot {
+            AccessorySlot::Ring => self.ring.take(),
+            AccessorySlot::Amulet => self.amulet.take(),
+        }
+    }
+    /// Percentage of bonus experience from defeated monsters.
+    pub fn xp_bonus(&self) -> usize {
+        if self.wears(ScholarsRing) {
+            SCHOLAR_XP_PCT
+        } else {
+            0
+        }
+    }
+    /// MP restored with each step through a maze.
+    pub fn step_mp(&self) -> i64 {
+        if self.wears(ManaRing) {
+            MANA_RING_MP
+        } else {
+            0
+        }
+    }
+    pub fn is_poison_immune(&self) -> bool {
+        self.wears(SerpentRing)
+    }
+    pub fn unequip(&mut self, kind: Gear) -> Piece {
+        let empty = if self.weapon.kind == kind { Fist } else { Bare };
+        match self.get_mut(kind) {
+            Some(slot) if !matches!(kind, Fist | Bare) => mem::replace(slot, Piece::from(empty)),
+            _ => Piece::from(Bare),
         }
     }
 }
@@ -226,11 +367,14 @@ impl Default for Equipment {
     // }
     fn default() -> Self {
         Self {
-            weapon: Gear::Fist,
-            head: Gear::Bare,
-            chest: Gear::Bare,
-            leg: Gear::Bare,
-            hand: Gear::Bare,
+            weapon: Piece::from(Fist),
+            offhand: Piece::from(Bare),
+            head: Piece::from(Bare),
+            chest: Piece::from(Bare),
+            leg: Piece::from(Bare),
+            hand: Piece::from(Bare),
+            ring: None,
+            amulet: None,
         }
     }
 }
@@ -240,7 +384,9 @@ impl fmt::Display for Equipment {
         writeln!(f, "{}:", "Equipment".underline())?;
         writeln!(
             f,
-            "                              |  {}  | {}",
+            "                              | {} | {} |  {}  | {}",
+            "rarity   ".underline(),
+            "durability".underline(),
             "value".underline(),
             "effect".underline(),
         )?;
@@ -249,9 +395,11 @@ impl fmt::Display for Equipment {
             ($item:expr, $slot:literal) => {
                 writeln!(
                     f,
-                    "{:<10} {:<40} | {:>2} {} | {:<30}",
+                    "{:<10} {:<40} | {:<9} | {:^10} | {:>2} {} | {:<30}",
                     $slot,
                     format!("{}", $item),
+                    $item.rarity.name(),
+                    $item.durability_str(),
                     $item.cost(),
                     GOLD,
                     $item.description(),
@@ -259,10 +407,488 @@ impl fmt::Display for Equipment {
             };
         }
         writeln_item!(self.weapon, "Weapon");
+        writeln_item!(self.offhand, "Off-hand");
         writeln_item!(self.head, "Head");
         writeln_item!(self.chest, "Chest");
         writeln_item!(self.leg, "Leg");
         writeln_item!(self.hand, "Hand");
+        for slot in [AccessorySlot::Ring, AccessorySlot::Amulet] {
+            match self.accessory(slot) {
+                Some(x) => writeln!(f, "{:<10} {:<40} | {}", slot, x, x.description())?,
+                None => writeln!(f, "{:<10} {:<40} |", slot, "-")?,
+            }
+        }
+        for set in GearSet::all() {
+            let count = self.set_count(set);
+            writeln!(f, "{} set ({}/4):", set, count)?;
+            for (n, bonus) in set.tiers() {
+                let status = if count >= n { "✅" } else { "  " };
+                writeln!(f, "  {status} {n} pieces: {bonus}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The stats provided by equipment, or a change in them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub(crate) strength: i64,
+    pub(crate) intellect: i64,
+    pub(crate) armor: i64,
+    pub(crate) max_hp: i64,
+    pub(crate) fire_damage: i64,
+    pub(crate) lifesteal: i64,
+}
+
+impl ops::Sub for Stats {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            strength: self.strength - rhs.strength,
+            intellect: self.intellect - rhs.intellect,
+            armor: self.armor - rhs.armor,
+            max_hp: self.max_hp - rhs.max_hp,
+            fire_damage: self.fire_damage - rhs.fire_damage,
+            lifesteal: self.lifesteal - rhs.lifesteal,
+        }
+    }
+}
+
+/// Formats as a change, e.g. `+2 STR, -1 ARMOR`.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = [
+            ("STR", self.strength),
+            ("INT", self.intellect),
+            ("ARMOR", self.armor),
+            ("HP", self.max_hp),
+            ("fire", self.fire_damage),
+            ("% lifesteal", self.lifesteal),
+        ];
+        let mut changed = stats.into_iter().filter(|(_, x)| *x != 0).peekable();
+        if changed.peek().is_none() {
+            return write!(f, "no change");
+        }
+        for (i, (name, x)) in changed.enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            let sep = if name.starts_with('%') { "" } else { " " };
+            let s = format!("{x:+}{sep}{name}");
+            if x > 0 {
+                write!(f, "{}", s.green())?;
+            } else {
+                write!(f, "{}", s.red())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bonuses granted by wearing several pieces of a set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SetBonus {
+    pub(crate) strength: i64,
+    pub(crate) intellect: i64,
+    pub(crate) armor: i64,
+    pub(crate) max_hp: i64,
+}
+
+impl ops::Add for SetBonus {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            strength: self.strength + rhs.strength,
+            intellect: self.intellect + rhs.intellect,
+            armor: self.armor + rhs.armor,
+            max_hp: self.max_hp + rhs.max_hp,
+        }
+    }
+}
+
+impl fmt::Display for SetBonus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = [
+            ("STR", self.strength),
+            ("INT", self.intellect),
+            ("ARMOR", self.armor),
+            ("HP", self.max_hp),
+        ];
+        for (i, (name, x)) in stats.into_iter().filter(|(_, x)| *x != 0).enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "+{x} {name}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GearSet {
+    Plate,
+    Wizard,
+}
+
+impl GearSet {
+    pub(crate) const fn all() -> [GearSet; 2] {
+        [Self::Plate, Self::Wizard]
+    }
+    pub const fn pieces(&self) -> [Gear; 4] {
+        match self {
+            Self::Plate => [Helmet, Breastplate, Greaves, Gauntlet],
+            Self::Wizard => [Hat, Robe, Breeches, Glove],
+        }
+    }
+    /// The bonus gained upon wearing each number of pieces.
+    pub const fn tiers(&self) -> [(usize, SetBonus); 3] {
+        const fn bonus(strength: i64, intellect: i64, armor: i64, max_hp: i64) -> SetBonus {
+            SetBonus {
+                strength,
+                intellect,
+                armor,
+                max_hp,
+            }
+        }
+        match self {
+            Self::Plate => [
+                (2, bonus(0, 0, 1, 0)),
+                (3, bonus(2, 0, 0, 0)),
+                (4, bonus(0, 0, 0, 25)),
+            ],
+            Self::Wizard => [
+                (2, bonus(0, 1, 0, 0)),
+                (3, bonus(0, 0, 1, 0)),
+                (4, bonus(0, 3, 0, 10)),
+            ],
+        }
+    }
+    pub fn active_tiers(&self, count: usize) -> impl Iterator<Item = SetBonus> {
+        self.tiers()
+            .into_iter()
+            .filter(move |(n, _)| count >= *n)
+            .map(|(_, bonus)| bonus)
+    }
+}
+
+impl fmt::Display for GearSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plate => write!(f, "{}", "Plate".underline()),
+            Self::Wizard => write!(f, "{}", "Wizard".underline()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rarity {
+    Common,
+    Magic,
+    Rare,
+    Legendary,
+}
+
+impl Rarity {
+    pub const fn affix_count(&self) -> usize {
+        match self {
+            Self::Common => 0,
+            Self::Magic => 1,
+            Self::Rare => 2,
+            Self::Legendary => 3,
+        }
+    }
+    pub const fn value_multiplier(&self) -> usize {
+        match self {
+            Self::Common => 1,
+            Self::Magic => 2,
+            Self::Rare => 4,
+            Self::Legendary => 8,
+        }
+    }
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Common => "common",
+            Self::Magic => "magic",
+            Self::Rare => "rare",
+            Self::Legendary => "legendary",
+        }
+    }
+    pub(crate) const fn color(&self) -> Color {
+        match self {
+            Self::Common => Color::Rgb(0x8a, 0x2b, 0xe2),
+            Self::Magic => Color::Rgb(0x1e, 0x90, 0xff),
+            Self::Rare => Color::Rgb(0xff, 0xd7, 0x00),
+            Self::Legendary => Color::Rgb(0xff, 0x8c, 0x00),
+        }
+    }
+    /// Better rarities become more likely as the monster `level` rises.
+    pub fn gen<T: Rng>(rng: &mut T, level: usize) -> Self {
+        match rng.gen_range(0..100) + level.min(50) {
+            0..60 => Self::Common,
+            60..85 => Self::Magic,
+            85..100 => Self::Rare,
+            _ => Self::Legendary,
+        }
+    }
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name().fg(self.color()))
+    }
+}
+
+/// A randomly rolled bonus carried by a piece of gear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Affix {
+    Strength(i64),
+    Intellect(i64),
+    MaxHp(i64),
+    /// Bonus fire damage dealt by each melee attack
+    FireDamage(i64),
+    /// Percentage of melee damage which heals the player
+    Lifesteal(i64),
+}
+
+impl Affix {
+    /// Magnitudes scale with the monster `level`.
+    pub fn gen<T: Rng>(rng: &mut T, level: usize) -> Self {
+        let level = level.max(1) as i64;
+        match rng.gen_range(0..5) {
+            0 => Self::Strength(rng.gen_range(1..=1 + level / 3)),
+            1 => Self::Intellect(rng.gen_range(1..=1 + level / 3)),
+            2 => Self::MaxHp(5 * rng.gen_range(1..=1 + level / 3)),
+            3 => Self::FireDamage(rng.gen_range(2..=2 + level / 2)),
+            _ => Self::Lifesteal(rng.gen_range(5..=(5 + level / 5).min(25))),
+        }
+    }
+}
+
+impl fmt::Display for Affix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Strength(x) => write!(f, "+{x} STR"),
+            Self::Intellect(x) => write!(f, "+{x} INT"),
+            Self::MaxHp(x) => write!(f, "+{x} HP"),
+            Self::FireDamage(x) => write!(f, "+{x} fire"),
+            Self::Lifesteal(x) => write!(f, "{x}% lifesteal"),
+        }
+    }
+}
+
+/// A distinct instance of gear, with its rarity and rolled affixes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Piece {
+    pub(crate) kind: Gear,
+    pub(crate) rarity: Rarity,
+    pub(crate) affixes: Vec<Affix>,
+    pub(crate) durability: usize,
+    /// From +0 to `MAX_UPGRADE`
+    pub(crate) upgrade: u8,
+    pub(crate) enchantment: Option<Offense>,
+}
+
+pub const MAX_UPGRADE: u8 = 10;
+/// Damage taken for each point of durability lost by armor.
+pub const DAMAGE_PER_WEAR: usize = 10;
+
+impl From<Gear> for Piece {
+    fn from(kind: Gear) -> Self {
+        Self {
+            kind,
+            rarity: Rarity::Common,
+            affixes: Vec::new(),
+            durability: kind.max_durability(),
+            upgrade: 0,
+            enchantment: None,
+        }
+    }
+}
+
+impl Piece {
+    pub fn gen<T: Rng>(rng: &mut T, kind: Gear, level: usize) -> Self {
+        let rarity = Rarity::gen(rng, level);
+        let affixes = (0..rarity.affix_count())
+            .map(|_| Affix::gen(rng, level))
+            .collect();
+        Self {
+            kind,
+            rarity,
+            affixes,
+            ..Self::from(kind)
+        }
+    }
+    pub const fn kind(&self) -> Gear {
+        self.kind
+    }
+    pub const fn weight(&self) -> usize {
+        self.kind.weight()
+    }
+    /// Broken gear provides no bonuses until it is repaired.
+    pub const fn is_broken(&self) -> bool {
+        self.kind.max_durability() > 0 && self.durability == 0
+    }
+    pub const fn is_damaged(&self) -> bool {
+        self.durability < self.kind.max_durability()
+    }
+    /// Lose `amount` durability; returns `true` if the piece just broke.
+    pub fn wear(&mut self, amount: usize) -> bool {
+        if self.durability > 0 {
+            self.durability = self.durability.saturating_sub(amount);
+            self.durability == 0
+        } else {
+            false
+        }
+    }
+    pub fn repair(&mut self) {
+        self.durability = self.kind.max_durability();
+    }
+    /// Gold charged by the blacksmith, in proportion to the durability lost.
+    pub const fn repair_cost(&self) -> usize {
+        let max = self.kind.max_durability();
+        if max == 0 {
+            0
+        } else {
+            (self.kind.cost() * (max - self.durability)).div_ceil(max)
+        }
+    }
+    pub(crate) fn durability_str(&self) -> String {
+        match self.kind.max_durability() {
+            0 => "-".to_string(),
+            max => format!("{}/{}", self.durability, max),
+        }
+    }
+    fn affix_sum(&self, f: impl Fn(&Affix) -> Option<i64>) -> i64 {
+        if self.is_broken() {
+            0
+        } else {
+            self.affixes.iter().filter_map(f).sum()
+        }
+    }
+    pub fn strength(&self) -> i64 {
+        if self.is_broken() {
+            return 0;
+        }
+        self.upgraded(self.kind.strength())
+            + self.affix_sum(|x| match x {
+                Affix::Strength(n) => Some(*n),
+                _ => None,
+            })
+    }
+    pub fn intellect(&self) -> i64 {
+        if self.is_broken() {
+            return 0;
+        }
+        self.upgraded(self.kind.intellect())
+            + self.affix_sum(|x| match x {
+                Affix::Intellect(n) => Some(*n),
+                _ => None,
+            })
+    }
+    pub const fn armor(&self) -> i64 {
+        if self.is_broken() {
+            0
+        } else {
+            self.kind.armor() + self.upgrade as i64 / 4
+        }
+    }
+    /// Each upgrade level raises a base stat by 20%.
+    const fn upgraded(&self, base: i64) -> i64 {
+        base + base * self.upgrade as i64 / 5
+    }
+    pub const fn is_one_handed(&self) -> bool {
+        self.kind.is_weapon() && !self.kind.is_two_handed()
+    }
+    pub const fn is_weapon(&self) -> bool {
+        self.kind.is_weapon()
+    }
+    /// Percent chance for a shield to block a melee attack.
+    pub const fn block_chance(&self) -> i64 {
+        if self.kind.is_shield() && !self.is_broken() {
+            20 + 2 * self.upgrade as i64
+        } else {
+            0
+        }
+    }
+    pub const fn can_upgrade(&self) -> bool {
+        !matches!(self.kind, Fist | Bare) && self.upgrade < MAX_UPGRADE
+    }
+    /// Gold needed to upgrade to the next level.
+    pub const fn upgrade_gold(&self) -> usize {
+        self.kind.cost() * (self.upgrade as usize + 1)
+    }
+    /// Each upgrade needs as many materials as the last, or more.
+    pub fn upgrade_materials(&self) -> MultiSet<Material> {
+        let next = self.upgrade + 1;
+        let material = match next {
+            0..=3 => Bone,
+            4..=6 => TrollHide,
+            _ => DragonScale,
+        };
+        [(material, (next as usize).div_ceil(2))]
+            .into_iter()
+            .collect()
+    }
+    /// Percent chance that the next upgrade succeeds.
+    pub const fn upgrade_chance(&self) -> u32 {
+        match self.upgrade + 1 {
+            0..=5 => 100,
+            n => 100 - 15 * (n as u32 - 5),
+        }
+    }
+    /// Elemental damage dealt by each melee attack with an enchanted weapon.
+    pub const fn enchantment_damage(&self) -> i64 {
+        match self.enchantment {
+            Some(element) if !self.is_broken() => element.damage() / 5 + 2 * self.upgrade as i64,
+            _ => 0,
+        }
+    }
+    pub fn max_hp(&self) -> i64 {
+        self.affix_sum(|x| match x {
+            Affix::MaxHp(n) => Some(*n),
+            _ => None,
+        })
+    }
+    pub fn fire_damage(&self) -> i64 {
+        self.affix_sum(|x| match x {
+            Affix::FireDamage(n) => Some(*n),
+            _ => None,
+        })
+    }
+    pub fn lifesteal(&self) -> i64 {
+        self.affix_sum(|x| match x {
+            Affix::Lifesteal(n) => Some(*n),
+            _ => None,
+        })
+    }
+    /// Worth less the more worn it is.
+    pub const fn cost(&self) -> usize {
+        let cost = self.kind.cost() * (self.rarity.value_multiplier() + self.upgrade as usize / 2);
+        match self.kind.max_durability() {
+            0 => cost,
+            max => cost * self.durability / max,
+        }
+    }
+    pub(crate) fn description(&self) -> String {
+        let mut s = self.kind.description();
+        if let Some(element) = self.enchantment {
+            s.push_str(&format!("  {element}"));
+        }
+        for (i, affix) in self.affixes.iter().enumerate() {
+            s.push_str(if i == 0 { "  " } else { ", " });
+            s.push_str(&affix.to_string());
+        }
+        s
+    }
+}
+
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind.name().fg(self.rarity.color()))?;
+        if self.upgrade > 0 {
+            write!(f, " {}", format!("+{}", self.upgrade).bold())?;
+        }
         Ok(())
     }
 }
@@ -294,12 +920,24 @@ pub enum Gear {
     // Hand
     Gauntlet,
     Glove,
+    // Off-hand
+    Shield,
     // default state
     Bare,
 }
 impl Gear {
     pub(crate) const fn total_variants() -> usize {
-        12
+        13
+    }
+    pub const fn is_weapon(&self) -> bool {
+        matches!(self, Self::Sword | Self::Axe | Self::Wand | Self::Staff)
+    }
+    /// Two-handed weapons occupy the off-hand as well.
+    pub const fn is_two_handed(&self) -> bool {
+        matches!(self, Self::Axe | Self::Staff)
+    }
+    pub const fn is_shield(&self) -> bool {
+        matches!(self, Self::Shield)
     }
     pub const fn strength(&self) -> i64 {
         match self {
@@ -333,13 +971,37 @@ impl Gear {
             Self::Robe => 1,
             Self::Breeches => 1,
             Self::Glove => 1,
+            Self::Shield => 1,
             _ => 0,
         }
     }
+    pub const fn weight(&self) -> usize {
+        match self {
+            Self::Fist | Self::Bare => 0,
+            Self::Axe => 6,
+            Self::Sword | Self::Staff => 4,
+            Self::Breastplate => 8,
+            Self::Greaves | Self::Shield => 5,
+            Self::Helmet => 3,
+            Self::Gauntlet | Self::Robe | Self::Breeches => 2,
+            Self::Wand | Self::Hat | Self::Glove => 1,
+        }
+    }
+    /// Bare hands never break.
+    pub const fn max_durability(&self) -> usize {
+        match self {
+            Self::Fist | Self::Bare => 0,
+            Self::Sword | Self::Axe => 60,
+            Self::Wand | Self::Staff => 40,
+            _ => 50,
+        }
+    }
     pub const fn cost(&self) -> usize {
         let lhs = self.strength() as usize;
         let rhs = self.intellect() as usize;
-        10 * if lhs > rhs { lhs } else { rhs }
+        let stat = if lhs > rhs { lhs } else { rhs };
+        let armor = self.armor() as usize;
+        10 * if stat > armor { stat } else { armor }
     }
     pub(crate) fn description(&self) -> String {
         format!(
@@ -349,6 +1011,25 @@ impl Gear {
             self.armor()
         )
     }
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Fist => "fist",
+            Self::Axe => "axe",
+            Self::Sword => "sword",
+            Self::Wand => "wand",
+            Self::Staff => "staff",
+            Self::Helmet => "helmet",
+            Self::Hat => "hat",
+            Self::Breastplate => "breastplate",
+            Self::Robe => "robe",
+            Self::Greaves => "greaves",
+            Self::Breeches => "breeches",
+            Self::Gauntlet => "gauntlet",
+            Self::Glove => "glove",
+            Self::Shield => "shield",
+            Self::Bare => "bare",
+        }
+    }
     pub(crate) fn from_index_trunc(idx: u8) -> Self {
         match idx {
             0 => Self::Sword,
@@ -362,7 +1043,8 @@ impl Gear {
             8 => Self::Greaves,
             9 => Self::Breeches,
             10 => Self::Gauntlet,
-            _ => Self::Glove,
+            11 => Self::Glove,
+            _ => Self::Shield,
         }
     }
 }
@@ -388,6 +1070,7 @@ impl FromStr for Gear {
         static_regex! { RE_BREECHES, "(?i)^breeches$" }
         static_regex! { RE_GAUNTLET, "(?i)^gauntlet$" }
         static_regex! { RE_GLOVE, "(?i)^glove$" }
+        static_regex! { RE_SHIELD, "(?i)^shield$" }
 
         macro_rules! branches {
             { ($var:ident, $variant:ident) ; $(($var_rest:ident, $variant_rest:ident),)+ } => {
@@ -403,33 +1086,13 @@ impl FromStr for Gear {
                      (RE_STAFF, Staff), (RE_HELMET, Helmet), (RE_HAT, Hat),
                      (RE_BREASTPLATE, Breastplate), (RE_ROBE, Robe),
                      (RE_GREAVES, Greaves), (RE_BREECHES, Breeches),
-                     (RE_GAUNTLET, Gauntlet), (RE_GLOVE, Glove),
+                     (RE_GAUNTLET, Gauntlet), (RE_GLOVE, Glove), (RE_SHIELD, Shield),
         }
     }
 }
 impl fmt::Display for Gear {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        macro_rules! arm {
-            ($word:literal) => {
-                write!(f, "{}", $word.rgb(0x8a, 0x2b, 0xe2))
-            };
-        }
-        match self {
-            Self::Fist => arm!("fist"),
-            Self::Axe => arm!("axe"),
-            Self::Sword => arm!("sword"),
-            Self::Wand => arm!("wand"),
-            Self::Staff => arm!("staff"),
-            Self::Helmet => arm!("helmet"),
-            Self::Hat => arm!("hat"),
-            Self::Breastplate => arm!("breastplate"),
-            Self::Robe => arm!("robe"),
-            Self::Greaves => arm!("greaves"),
-            Self::Breeches => arm!("breeches"),
-            Self::Gauntlet => arm!("gauntlet"),
-            Self::Glove => arm!("glove"),
-            Self::Bare => arm!("bare"),
-        }
+        write!(f, "{}", self.name().fg(Rarity::Common.color()))
     }
 }
 
@@ -440,12 +1103,182 @@ mod tests {
     #[test]
     fn default() {
         let lhs = Equipment {
-            weapon: Gear::Fist,
-            head: Gear::Bare,
-            chest: Gear::Bare,
-            leg: Gear::Bare,
-            hand: Gear::Bare,
+            weapon: Piece::from(Gear::Fist),
+            offhand: Piece::from(Gear::Bare),
+            head: Piece::from(Gear::Bare),
+            chest: Piece::from(Gear::Bare),
+            leg: Piece::from(Gear::Bare),
+            hand: Piece::from(Gear::Bare),
+            ring: None,
+            amulet: None,
         };
         assert_eq!(lhs, Equipment::default())
     }
+
+    #[test]
+    fn affixes() {
+        let sword = Piece {
+            kind: Sword,
+            rarity: Rarity::Rare,
+            affixes: vec![Affix::Strength(3), Affix::Lifesteal(10)],
+            ..Piece::from(Sword)
+        };
+        assert_eq!(sword.strength(), Sword.strength() + 3);
+        assert_eq!(sword.lifesteal(), 10);
+        assert_eq!(sword.cost(), 4 * Sword.cost());
+
+        let mut equipment = Equipment::default();
+        assert_eq!(equipment.equip(sword.clone()), vec![]);
+        assert_eq!(equipment.strength(), sword.strength());
+        assert_eq!(equipment.unequip(Sword), sword);
+        assert_eq!(equipment, Equipment::default());
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let piece = Piece::gen(&mut rng, Helmet, 10);
+            assert_eq!(piece.affixes.len(), piece.rarity.affix_count());
+        }
+    }
+
+    #[test]
+    fn compare() {
+        let mut equipment = Equipment::default();
+        equipment.equip(Piece::from(Sword));
+        let (old, delta) = equipment.compare(&Piece::from(Axe), false);
+        assert_eq!(old, vec![Piece::from(Sword)]);
+        assert_eq!(delta.strength, Axe.strength() - Sword.strength());
+        assert_eq!(delta.armor, 0);
+        assert_eq!(equipment.weapon(), &Piece::from(Sword));
+        let (old, delta) = equipment.compare(&Piece::from(Sword), true);
+        assert_eq!(old, vec![]);
+        assert_eq!(delta.strength, Sword.strength());
+        equipment.equip(Piece::from(Helmet));
+        let (old, delta) = equipment.compare(&Piece::from(Helmet), false);
+        assert_eq!(old, vec![Piece::from(Helmet)]);
+        assert_eq!(delta, Stats::default());
+    }
+
+    #[test]
+    fn hands() {
+        let mut equipment = Equipment::default();
+        assert_eq!(equipment.equip(Piece::from(Sword)), vec![]);
+        // Swapping weapons does not dual-wield them
+        assert_eq!(equipment.equip(Piece::from(Wand)), vec![Piece::from(Sword)]);
+        assert_eq!(equipment.offhand(), &Piece::from(Bare));
+        assert_eq!(equipment.equip_offhand(Piece::from(Sword)), vec![]);
+        assert_eq!(equipment.offhand(), &Piece::from(Sword));
+        assert_eq!(
+            equipment.equip_offhand(Piece::from(Axe)),
+            vec![Piece::from(Axe)]
+        );
+        assert_eq!(
+            equipment.equip(Piece::from(Axe)),
+            vec![Piece::from(Wand), Piece::from(Sword)]
+        );
+        assert_eq!(equipment.block_chance(), 0);
+        assert_eq!(equipment.equip(Piece::from(Shield)), vec![Piece::from(Axe)]);
+        assert_eq!(equipment.weapon(), &Piece::from(Fist));
+        assert_eq!(equipment.block_chance(), 20);
+        assert_eq!(equipment.equip(Piece::from(Sword)), vec![]);
+        assert_eq!(equipment.offhand(), &Piece::from(Shield));
+        assert_eq!(equipment.unequip(Shield), Piece::from(Shield));
+        assert_eq!(equipment.unequip(Sword), Piece::from(Sword));
+        assert_eq!(equipment, Equipment::default());
+    }
+
+    #[test]
+    fn set_bonus() {
+        let mut equipment = Equipment::default();
+        equipment.equip(Piece::from(Helmet));
+        assert_eq!(equipment.set_bonus(), SetBonus::default());
+        equipment.equip(Piece::from(Greaves));
+        equipment.equip(Piece::from(Glove));
+        assert_eq!(equipment.set_count(GearSet::Plate), 2);
+        assert_eq!(equipment.set_count(GearSet::Wizard), 1);
+        assert_eq!(
+            equipment.armor(),
+            Helmet.armor() + Greaves.armor() + Glove.armor() + 1
+        );
+        equipment.equip(Piece::from(Breastplate));
+        equipment.equip(Piece::from(Gauntlet));
+        assert_eq!(equipment.set_bonus().strength, 2);
+        assert_eq!(equipment.max_hp(), 25);
+    }
+
+    #[test]
+    fn durability() {
+        let mut axe = Piece::from(Axe);
+        for _ in 1..Axe.max_durability() {
+            assert!(!axe.wear(1));
+        }
+        assert_eq!(axe.strength(), Axe.strength());
+        assert_eq!(axe.repair_cost(), Axe.cost());
+        assert!(axe.cost() < Piece::from(Axe).cost());
+        assert!(axe.wear(1));
+        assert!(axe.is_broken());
+        assert_eq!(axe.strength(), 0);
+        assert_eq!(axe.cost(), 0);
+        assert!(!axe.wear(1));
+        axe.repair();
+        assert_eq!(axe, Piece::from(Axe));
+        assert_eq!(axe.repair_cost(), 0);
+
+        let mut fist = Piece::from(Fist);
+        assert!(!fist.can_upgrade());
+        assert!(!fist.wear(1));
+        assert!(!fist.is_broken());
+
+        let mut equipment = Equipment::default();
+        equipment.equip(Piece::from(Helmet));
+        assert!(equipment.wear_armor(1).is_empty());
+        assert!(equipment.wear_armor(3 * DAMAGE_PER_WEAR).is_empty());
+        assert_eq!(equipment.head.durability, Helmet.max_durability() - 4);
+        assert_eq!(
+            equipment.wear_armor(Helmet.max_durability() * DAMAGE_PER_WEAR),
+            vec![Helmet]
+        );
+    }
+
+    #[test]
+    fn upgrade() {
+        let mut axe = Piece::from(Axe);
+        assert_eq!(axe.upgrade_materials().n_available(&Bone), 1);
+        let counts: Vec<usize> = (0..MAX_UPGRADE)
+            .map(|upgrade| {
+                Piece {
+                    upgrade,
+                    ..axe.clone()
+                }
+                .upgrade_materials()
+                .n_total()
+            })
+            .collect();
+        assert!(counts.is_sorted());
+        axe.upgrade = 5;
+        assert_eq!(axe.strength(), 2 * Axe.strength());
+        assert_eq!(axe.upgrade_chance(), 85);
+        axe.upgrade = MAX_UPGRADE;
+        assert!(!axe.can_upgrade());
+        assert_eq!(axe.enchantment_damage(), 0);
+        axe.enchantment = Some(Offense::Fire);
+        assert_eq!(axe.enchantment_damage(), Offense::Fire.damage() / 5 + 20);
+    }
+
+    #[test]
+    fn accessories() {
+        let mut equipment = Equipment::default();
+        assert_eq!(equipment.xp_bonus(), 0);
+        assert_eq!(equipment.equip_accessory(ScholarsRing), None);
+        assert_eq!(equipment.xp_bonus(), SCHOLAR_XP_PCT);
+        assert_eq!(equipment.equip_accessory(SerpentRing), Some(ScholarsRing));
+        assert!(equipment.is_poison_immune());
+        assert_eq!(equipment.equip_accessory(SeersAmulet), None);
+        assert!(equipment.wears(SerpentRing) && equipment.wears(SeersAmulet));
+        assert_eq!(
+            equipment.unequip_accessory(AccessorySlot::Ring),
+            Some(SerpentRing)
+        );
+        assert_eq!(equipment.unequip_accessory(AccessorySlot::Ring), None);
+        assert_eq!(equipment.step_mp(), 0);
+    }
 }
@@ -11,6 +11,8 @@ use yansi::{Paint, Painted};
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EquipmentAction {
     Equip,
+    /// Equip in the off-hand
+    Offhand,
     Unequip,
     Quit,
 }
@@ -22,11 +24,15 @@ impl FromStr for EquipmentAction {
 
         static RE_EQUIP: LazyLock<Regex> =
             LazyLock::new(|| Regex::new("(?i)^(?:equip|e)$").unwrap());
+        static RE_OFFHAND: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:offhand|o)$").unwrap());
         static RE_UNEQUIP: LazyLock<Regex> =
             LazyLock::new(|| Regex::new("(?i)^(?:unequip|u)$").unwrap());
 
         if RE_EQUIP.is_match(s) {
             Ok(EquipmentAction::Equip)
+        } else if RE_OFFHAND.is_match(s) {
+            Ok(EquipmentAction::Offhand)
         } else if RE_UNEQUIP.is_match(s) {
             Ok(EquipmentAction::Unequip)
         } else if is_quit(s) {
@@ -37,9 +43,31 @@ impl FromStr for EquipmentAction {
     }
 }
 
+/// Gear in the bag, referred to either by kind or by its listed number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GearRef {
+    Kind(Gear),
+    Index(usize),
+}
+
+impl FromStr for GearRef {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(idx) = s.parse::<usize>() {
+            Ok(GearRef::Index(idx))
+        } else if let Ok(kind) = s.parse::<Gear>() {
+            Ok(GearRef::Kind(kind))
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EquipmentTransaction {
-    Equip(Gear),
+    Equip(GearRef),
+    Offhand(GearRef),
     Unequip(Gear),
     Quit,
 }
@@ -48,18 +76,23 @@ impl FromStr for EquipmentTransaction {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
         if let Some((lhs, rhs)) = s.split_once(' ') {
-            if let Ok(action) = lhs.parse::<EquipmentAction>() {
-                if let Ok(gear) = rhs.parse::<Gear>() {
-                    match action {
-                        EquipmentAction::Equip => {
-                            return Ok(EquipmentTransaction::Equip(gear));
-                        }
-                        EquipmentAction::Unequip => {
-                            return Ok(EquipmentTransaction::Unequip(gear));
-                        }
-                        EquipmentAction::Quit => (),
+            match lhs.parse::<EquipmentAction>() {
+                Ok(EquipmentAction::Equip) => {
+                    if let Ok(gear) = rhs.parse::<GearRef>() {
+                        return Ok(EquipmentTransaction::Equip(gear));
                     }
                 }
+                Ok(EquipmentAction::Offhand) => {
+                    if let Ok(gear) = rhs.parse::<GearRef>() {
+                        return Ok(EquipmentTransaction::Offhand(gear));
+                    }
+                }
+                Ok(EquipmentAction::Unequip) => {
+                    if let Ok(gear) = rhs.parse::<Gear>() {
+                        return Ok(EquipmentTransaction::Unequip(gear));
+                    }
+                }
+                Ok(EquipmentAction::Quit) | Err(_) => (),
             }
         } else if let Ok(EquipmentAction::Quit) = s.parse::<EquipmentAction>() {
             return Ok(EquipmentTransaction::Quit);
@@ -69,7 +102,7 @@ impl FromStr for EquipmentTransaction {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct EquipmentBag(MultiSet<Gear>);
+pub struct EquipmentBag(MultiSet<Piece>);
 
 impl EquipmentBag {
     pub fn new() -> Self {
@@ -86,24 +119,6 @@ impl EquipmentBag {
         .into_iter()
         .collect()
     }
-    pub fn new_merchant() -> Self {
-        [
-            (Sword, 1),
-            (Axe, 1),
-            (Helmet, 1),
-            (Breastplate, 1),
-            (Greaves, 1),
-            (Gauntlet, 1),
-            (Wand, 1),
-            (Staff, 1),
-            (Hat, 1),
-            (Robe, 1),
-            (Breeches, 1),
-            (Glove, 1),
-        ]
-        .into_iter()
-        .collect()
-    }
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
@@ -131,57 +146,133 @@ impl EquipmentBag {
             }
         }
     }
-    pub fn pop_item(&mut self, kind: Gear) -> Option<Gear> {
-        self.0.pop(kind)
+    /// The distinct pieces in the bag, in the order in which they are listed.
+    pub(crate) fn pieces(&self) -> impl Iterator<Item = (&Piece, usize)> {
+        self.0
+            .bag
+            .iter()
+            .filter(|(piece, count)| **count > 0 && !matches!(piece.kind, Fist | Bare))
+            .map(|(piece, count)| (piece, *count))
+    }
+    pub fn get(&self, gear: GearRef) -> Option<&Piece> {
+        match gear {
+            GearRef::Kind(kind) => self
+                .pieces()
+                .map(|(piece, _)| piece)
+                .find(|piece| piece.kind == kind),
+            GearRef::Index(idx) => self
+                .pieces()
+                .nth(idx.wrapping_sub(1))
+                .map(|(piece, _)| piece),
+        }
+    }
+    pub fn pop_item(&mut self, gear: GearRef) -> Option<Piece> {
+        let piece = self.get(gear)?.clone();
+        self.0.pop(piece)
     }
-    pub fn pop_multiple(&mut self, kind: Gear, n: usize) -> Option<(Gear, usize)> {
-        self.0.pop_multiple(kind, n)
+    /// Up to `n` pieces of the given kind, least valuable first.
+    pub fn cheapest(&self, kind: Gear, n: usize) -> Vec<Piece> {
+        let mut pieces: Vec<_> = self
+            .pieces()
+            .filter(|(piece, _)| piece.kind == kind)
+            .flat_map(|(piece, count)| std::iter::repeat_n(piece.clone(), count))
+            .collect();
+        pieces.sort_by_key(Piece::cost);
+        pieces.truncate(n);
+        pieces
+    }
+    /// Remove up to `n` pieces of the given kind, least valuable first.
+    pub fn pop_multiple(&mut self, kind: Gear, n: usize) -> Vec<Piece> {
+        let pieces = self.cheapest(kind, n);
+        for piece in pieces.iter() {
+            self.0.pop(piece.clone());
+        }
+        pieces
+    }
+    /// Remove exactly the given pieces, or nothing if any is missing.
+    pub fn take(&mut self, pieces: &[Piece]) -> bool {
+        let pieces: MultiSet<Piece> = pieces.iter().map(|x| (x.clone(), 1)).collect();
+        if self.0.contains_all(&pieces) {
+            self.0.remove_all(&pieces);
+            true
+        } else {
+            false
+        }
     }
     pub fn drop_multiple(&mut self, kind: Gear, n: usize) {
-        self.0.remove_multiple(kind, n);
+        self.pop_multiple(kind, n);
     }
-    pub fn drop_item(&mut self, kind: Gear) {
-        self.0.pop(kind);
+    pub fn drop_item(&mut self, gear: GearRef) {
+        self.pop_item(gear);
     }
-    pub fn push_multiple(&mut self, kind: Gear, count: usize) {
-        match kind {
+    pub fn push_multiple(&mut self, piece: Piece, count: usize) {
+        match piece.kind {
             Bare | Fist => (),
-            kind => self.0.push_multiple(kind, count),
+            _ => self.0.push_multiple(piece, count),
         }
     }
-    pub fn push(&mut self, kind: Gear) {
-        match kind {
-            Bare | Fist => (),
-            kind => self.0.push(kind),
+    pub fn push(&mut self, piece: Piece) {
+        self.push_multiple(piece, 1)
+    }
+    /// Pieces in need of repair, optionally restricted to one kind.
+    pub(crate) fn damaged(&self, kind: Option<Gear>) -> impl Iterator<Item = (&Piece, usize)> {
+        self.pieces()
+            .filter(move |(x, _)| x.is_damaged() && kind.is_none_or(|kind| x.kind == kind))
+    }
+    pub fn repair(&mut self, kind: Option<Gear>) {
+        let damaged: Vec<_> = self
+            .damaged(kind)
+            .map(|(piece, count)| (piece.clone(), count))
+            .collect();
+        for (mut piece, count) in damaged {
+            self.0.remove_multiple(piece.clone(), count);
+            piece.repair();
+            self.0.push_multiple(piece, count);
         }
     }
+    /// Worn gear is not counted.
+    pub fn weight(&self) -> usize {
+        self.pieces()
+            .map(|(piece, count)| piece.weight() * count)
+            .sum()
+    }
     pub fn n_available(&self, kind: &Gear) -> usize {
-        self.0.n_available(kind)
+        self.pieces()
+            .filter(|(piece, _)| piece.kind == *kind)
+            .map(|(_, count)| count)
+            .sum()
     }
-    pub(crate) fn fmt_imp<T: fmt::Write>(&self, f: &mut T, field2: &'static str) -> fmt::Result {
+    /// List the pieces, with `price` giving the gold shown for each one.
+    pub(crate) fn fmt_imp<T: fmt::Write>(
+        &self,
+        f: &mut T,
+        field2: &'static str,
+        price: impl Fn(&Piece) -> usize,
+    ) -> fmt::Result {
         if self.is_empty() {
             writeln!(f, "EquipmentBag is empty!")?;
         } else {
             writeln!(f, "{}:", "EquipmentBag".bold().underline(),)?;
             writeln!(
                 f,
-                "                       | {} |  {}  |  {}",
+                "                           | {} | {} | {} |  {}  |  {}",
                 "available".underline(),
+                "rarity   ".underline(),
+                "durability".underline(),
                 field2.underline(),
                 "effect".underline(),
             )?;
             const GOLD: Painted<&'static str> = Painted::new("gold").bold().yellow();
-            for (item, count) in
-                self.0.bag.iter().filter(|(kind, count)| {
-                    **count > 0 && **kind != Gear::Fist && **kind != Gear::Bare
-                })
-            {
+            for (i, (item, count)) in self.pieces().enumerate() {
                 writeln!(
                     f,
-                    "    {:<40} | {:^9} | {:>2} {} | {:<30}",
+                    "{:>3} {:<40} | {:^9} | {:<9} | {:^10} | {:>3} {} | {:<30}",
+                    i + 1,
                     format!("{}", item),
                     count,
-                    item.cost(),
+                    item.rarity.name(),
+                    item.durability_str(),
+                    price(item),
                     GOLD,
                     item.description(),
                 )?;
@@ -195,12 +286,57 @@ impl FromIterator<(Gear, usize)> for EquipmentBag {
     where
         T: IntoIterator<Item = (Gear, usize)>,
     {
-        Self(iter.into_iter().collect())
+        let mut bag = Self::new();
+        for (kind, count) in iter {
+            bag.push_multiple(Piece::from(kind), count);
+        }
+        bag
     }
 }
 
 impl fmt::Display for EquipmentBag {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.fmt_imp(f, "value")
+        self.fmt_imp(f, "value", Piece::cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distinct_pieces() {
+        let mut bag: EquipmentBag = [(Sword, 2)].into_iter().collect();
+        let rare = Piece {
+            kind: Sword,
+            rarity: Rarity::Rare,
+            affixes: vec![Affix::FireDamage(4), Affix::MaxHp(10)],
+            ..Piece::from(Sword)
+        };
+        bag.push(rare.clone());
+        assert_eq!(bag.n_available(&Sword), 3);
+        assert_eq!(bag.pieces().count(), 2);
+        assert_eq!(bag.get(GearRef::Index(2)), Some(&rare));
+        assert_eq!(bag.get(GearRef::Index(0)), None);
+        assert_eq!(bag.pop_multiple(Sword, 2), vec![Piece::from(Sword); 2]);
+        assert_eq!(bag.pop_item(GearRef::Kind(Sword)), Some(rare));
+        assert!(bag.is_empty());
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "e 2".parse::<EquipmentTransaction>().unwrap(),
+            EquipmentTransaction::Equip(GearRef::Index(2))
+        );
+        assert_eq!(
+            "e sword".parse::<EquipmentTransaction>().unwrap(),
+            EquipmentTransaction::Equip(GearRef::Kind(Sword))
+        );
+        assert_eq!(
+            "offhand sword".parse::<EquipmentTransaction>().unwrap(),
+            EquipmentTransaction::Offhand(GearRef::Kind(Sword))
+        );
+        assert!("u 2".parse::<EquipmentTransaction>().is_err());
     }
 }
@@ -0,0 +1,186 @@
+use crate::{monster::MonsterKind, multiset::MultiSet};
+use regex::Regex;
+use std::{fmt, str::FromStr, sync::LazyLock};
+use yansi::Paint;
+
+/// A crafting material, dropped by one kind of monster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Material {
+    FrogLeg,
+    BatWing,
+    SnakeSkin,
+    WolfPelt,
+    GoblinEar,
+    BearClaw,
+    Bone,
+    OrcTusk,
+    VampireFang,
+    TrollHide,
+    MammothTusk,
+    DragonScale,
+    FairyDust,
+}
+pub use Material::*;
+
+impl Material {
+    pub(crate) const fn total_variants() -> usize {
+        13
+    }
+    pub const fn from_monster(kind: MonsterKind) -> Self {
+        match kind {
+            MonsterKind::Frog => FrogLeg,
+            MonsterKind::Bat => BatWing,
+            MonsterKind::Snake => SnakeSkin,
+            MonsterKind::Wolf => WolfPelt,
+            MonsterKind::Goblin => GoblinEar,
+            MonsterKind::Bear => BearClaw,
+            MonsterKind::Undead => Bone,
+            MonsterKind::Orc => OrcTusk,
+            MonsterKind::Vampire => VampireFang,
+            MonsterKind::Troll => TrollHide,
+            MonsterKind::Mammoth => MammothTusk,
+            MonsterKind::Dragon => DragonScale,
+            MonsterKind::Fairy => FairyDust,
+        }
+    }
+    pub const fn singular(&self) -> &'static str {
+        match self {
+            FrogLeg => "frog leg",
+            BatWing => "bat wing",
+            SnakeSkin => "snake skin",
+            WolfPelt => "wolf pelt",
+            GoblinEar => "goblin ear",
+            BearClaw => "bear claw",
+            Bone => "bone",
+            OrcTusk => "orc tusk",
+            VampireFang => "vampire fang",
+            TrollHide => "troll hide",
+            MammothTusk => "mammoth tusk",
+            DragonScale => "dragon scale",
+            FairyDust => "fairy dust",
+        }
+    }
+    pub const fn plural(&self) -> &'static str {
+        match self {
+            FrogLeg => "frog legs",
+            BatWing => "bat wings",
+            SnakeSkin => "snake skins",
+            WolfPelt => "wolf pelts",
+            GoblinEar => "goblin ears",
+            BearClaw => "bear claws",
+            Bone => "bones",
+            OrcTusk => "orc tusks",
+            VampireFang => "vampire fangs",
+            TrollHide => "troll hides",
+            MammothTusk => "mammoth tusks",
+            DragonScale => "dragon scales",
+            FairyDust => "fairy dust",
+        }
+    }
+}
+
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.singular().rgb(0xcd, 0x85, 0x3f))
+    }
+}
+
+impl FromStr for Material {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        macro_rules! static_regex {
+            { $var:ident, $regex:literal } => {
+                static $var: LazyLock<Regex> = LazyLock::new(|| Regex::new($regex).unwrap());
+            }
+        }
+        static_regex! { RE_FROG_LEG, "(?i)^frog legs?$" }
+        static_regex! { RE_BAT_WING, "(?i)^bat wings?$" }
+        static_regex! { RE_SNAKE_SKIN, "(?i)^snake skins?$" }
+        static_regex! { RE_WOLF_PELT, "(?i)^wolf pelts?$" }
+        static_regex! { RE_GOBLIN_EAR, "(?i)^goblin ears?$" }
+        static_regex! { RE_BEAR_CLAW, "(?i)^bear claws?$" }
+        static_regex! { RE_BONE, "(?i)^bones?$" }
+        static_regex! { RE_ORC_TUSK, "(?i)^orc tusks?$" }
+        static_regex! { RE_VAMPIRE_FANG, "(?i)^vampire fangs?$" }
+        static_regex! { RE_TROLL_HIDE, "(?i)^troll hides?$" }
+        static_regex! { RE_MAMMOTH_TUSK, "(?i)^mammoth tusks?$" }
+        static_regex! { RE_DRAGON_SCALE, "(?i)^dragon scales?$" }
+        static_regex! { RE_FAIRY_DUST, "(?i)^fairy dust$" }
+
+        macro_rules! branches {
+            { ($var:ident, $variant:ident) ; $(($var_rest:ident, $variant_rest:ident),)+ } => {
+                if $var.is_match(s) {
+                    Ok($variant)
+                } $(else if $var_rest.is_match(s) { Ok($variant_rest)})+
+                else {
+                    Err(s.to_string())
+                }
+            }
+        }
+        branches! { (RE_FROG_LEG, FrogLeg) ; (RE_BAT_WING, BatWing), (RE_SNAKE_SKIN, SnakeSkin),
+                    (RE_WOLF_PELT, WolfPelt), (RE_GOBLIN_EAR, GoblinEar),
+                    (RE_BEAR_CLAW, BearClaw), (RE_BONE, Bone), (RE_ORC_TUSK, OrcTusk),
+                    (RE_VAMPIRE_FANG, VampireFang), (RE_TROLL_HIDE, TrollHide),
+                    (RE_MAMMOTH_TUSK, MammothTusk), (RE_DRAGON_SCALE, DragonScale),
+                    (RE_FAIRY_DUST, FairyDust),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaterialBag(pub(crate) MultiSet<Material>);
+
+impl MaterialBag {
+    pub fn new() -> Self {
+        Self(MultiSet::with_capacity(Material::total_variants()))
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub fn push_multiple(&mut self, kind: Material, count: usize) {
+        self.0.push_multiple(kind, count);
+    }
+    pub fn n_available(&self, kind: &Material) -> usize {
+        self.0.n_available(kind)
+    }
+    pub fn contains_all(&self, ingredients: &MultiSet<Material>) -> bool {
+        self.0.contains_all(ingredients)
+    }
+    pub fn remove_all(&mut self, ingredients: &MultiSet<Material>) {
+        self.0.remove_all(ingredients);
+    }
+}
+
+impl Default for MaterialBag {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for MaterialBag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            writeln!(f, "Materials bag is empty!")
+        } else {
+            writeln!(f, "{}:", "Materials".bold().underline())?;
+            for (item, count) in self.0.bag.iter().filter(|(_, count)| **count > 0) {
+                writeln!(f, "    {:<30} | {:^9}", format!("{}", item), count)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!("frog leg".parse::<Material>().unwrap(), FrogLeg);
+        assert_eq!("Dragon Scales".parse::<Material>().unwrap(), DragonScale);
+        assert_eq!("fairy dust".parse::<Material>().unwrap(), FairyDust);
+        assert!("dragon".parse::<Material>().is_err());
+    }
+}
@@ -1,6 +1,8 @@
+pub mod accessory;
 pub mod consumable;
 pub mod equipment;
 pub mod equipment_bag;
+pub mod material;
 
 use self::consumable::*;
 use self::equipment::*;
@@ -21,6 +23,12 @@ impl Item {
             Self::Gear(x) => x.cost(),
         }
     }
+    pub fn weight(&self) -> usize {
+        match self {
+            Self::Consumable(x) => x.weight(),
+            Self::Gear(x) => x.weight(),
+        }
+    }
 }
 macro_rules! impl_from {
     { $T:ident } => {
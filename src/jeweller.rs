@@ -0,0 +1,178 @@
+use crate::{accessory::*, player::Player, utils::is_quit};
+use regex::Regex;
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::{Paint, Painted};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JewelTransaction {
+    Buy(Accessory),
+    Sell(Accessory),
+    Wear(Accessory),
+    Remove(AccessorySlot),
+    Quit,
+}
+
+impl FromStr for JewelTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_BUY: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:buy|b)$").unwrap());
+        static RE_SELL: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:sell|s)$").unwrap());
+        static RE_WEAR: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:wear|w)$").unwrap());
+        static RE_REMOVE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:remove|r)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.split_once(' ') {
+            if RE_REMOVE.is_match(lhs) {
+                if let Ok(slot) = rhs.parse::<AccessorySlot>() {
+                    return Ok(Self::Remove(slot));
+                }
+            } else if let Ok(accessory) = rhs.parse::<Accessory>() {
+                if RE_BUY.is_match(lhs) {
+                    return Ok(Self::Buy(accessory));
+                } else if RE_SELL.is_match(lhs) {
+                    return Ok(Self::Sell(accessory));
+                } else if RE_WEAR.is_match(lhs) {
+                    return Ok(Self::Wear(accessory));
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(Self::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+/// Share of an accessory's cost paid back when the player sells it.
+const SELL_PCT: usize = 50;
+
+/// Sells, buys and fits rings and amulets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jeweller;
+
+impl Jeweller {
+    /// Gold paid for a spare accessory.
+    pub fn sell_price(&self, x: Accessory) -> usize {
+        x.cost() * SELL_PCT / 100
+    }
+    pub fn message(&self, player: &Player) -> String {
+        const GOLD: Painted<&'static str> = Painted::new("gold").bold().yellow();
+        let mut s = String::with_capacity(1 << 10);
+        writeln!(s, "{}:", "For sale".bold().underline()).unwrap();
+        for x in Accessory::all() {
+            writeln!(
+                s,
+                "    {:<30} | {:>3} {} | {}",
+                format!("{}", x),
+                x.cost(),
+                GOLD,
+                x.description()
+            )
+            .unwrap();
+        }
+        for slot in [AccessorySlot::Ring, AccessorySlot::Amulet] {
+            match player.equipment.accessory(slot) {
+                Some(x) => writeln!(s, "{}: {x}", slot.bold().underline()).unwrap(),
+                None => writeln!(s, "{}: -", slot.bold().underline()).unwrap(),
+            }
+        }
+        write!(s, "{}", player.accessories_message()).unwrap();
+        s
+    }
+    pub fn menu(&self, gold: usize, msg: &str) -> JewelTransaction {
+        let mut buf = String::with_capacity(1 << 7);
+        println!("---- Visiting the jeweller... ----");
+        println!("{}", msg);
+        println!("e.g. `buy mana ring`, `sell phoenix`, `wear seer`, `remove ring`; `q` to leave");
+        let n = msg.lines().count() + 3;
+        loop {
+            String::clear(&mut buf);
+            print!("(💰: {}) 💍 ", gold);
+            io::Write::flush(&mut io::stdout()).unwrap();
+
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            match handle.read_line(&mut buf) {
+                Ok(_) => {
+                    let _ = crate::readline::clear_last_n_lines(1);
+                }
+                Err(e) => println!("Error in jeweller menu readline: {:#?}", e),
+            }
+            if let Ok(transaction) = buf.parse::<JewelTransaction>() {
+                let _ = crate::readline::clear_last_n_lines(n);
+                break transaction;
+            }
+        }
+    }
+    pub fn visit(&self, player: &mut Player) -> bool {
+        match self.menu(player.gold, &self.message(player)) {
+            JewelTransaction::Buy(x) => {
+                if player.gold < x.cost() {
+                    println!("Jeweller rejected transaction: insufficient gold!");
+                } else {
+                    player.gold -= x.cost();
+                    player.accessories.push(x);
+                    println!("You bought a {x} for {} gold.", x.cost());
+                }
+            }
+            JewelTransaction::Sell(x) => {
+                if player.accessories.pop(x).is_some() {
+                    let price = self.sell_price(x);
+                    player.gold += price;
+                    println!("You sold a {x} for {price} gold.");
+                } else {
+                    println!("Jeweller rejected transaction: you have no spare {x}!");
+                }
+            }
+            JewelTransaction::Wear(x) => {
+                if !player.wear_accessory(x) {
+                    println!("You have no spare {x}!");
+                }
+            }
+            JewelTransaction::Remove(slot) => player.remove_accessory(slot),
+            JewelTransaction::Quit => return false,
+        }
+        true
+    }
+    pub fn work(&self, player: &mut Player) {
+        while self.visit(player) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "buy mana ring".parse::<JewelTransaction>().unwrap(),
+            JewelTransaction::Buy(ManaRing)
+        );
+        assert_eq!(
+            "w Phoenix Amulet".parse::<JewelTransaction>().unwrap(),
+            JewelTransaction::Wear(PhoenixAmulet)
+        );
+        assert_eq!(
+            "remove ring".parse::<JewelTransaction>().unwrap(),
+            JewelTransaction::Remove(AccessorySlot::Ring)
+        );
+        assert_eq!(
+            "q".parse::<JewelTransaction>().unwrap(),
+            JewelTransaction::Quit
+        );
+        assert!("sell ring".parse::<JewelTransaction>().is_err());
+    }
+
+    #[test]
+    fn sell_price() {
+        for x in Accessory::all() {
+            assert!(Jeweller.sell_price(x) < x.cost());
+        }
+    }
+}
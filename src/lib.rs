@@ -1,27 +1,43 @@
 pub mod adventure;
 pub mod attribute;
+pub mod bank;
+pub mod blacksmith;
+pub mod class;
+pub mod clock;
 pub mod combat;
+pub mod craft;
+pub mod death;
+pub mod dialogue;
 pub mod dungeon;
 pub mod encounter;
+pub mod experience;
+pub mod forge;
 pub mod game;
 pub mod grid;
+pub mod inn;
 pub mod inventory;
 pub mod item;
+pub mod jeweller;
 pub mod loot;
+pub mod market;
 pub mod maze;
 pub mod melee;
 pub mod monster;
 pub mod multiset;
 pub mod pathfinding;
 pub mod player;
+pub mod quest;
 pub mod readline;
 pub mod resource;
 pub mod scoreboard;
+pub mod skill;
 pub mod spell;
 pub mod town;
 pub mod trade;
 
 pub(crate) mod utils;
 
+pub use crate::item::accessory;
 pub use crate::item::consumable;
 pub use crate::item::equipment;
+pub use crate::item::material;
@@ -1,12 +1,25 @@
-use crate::{consumable::*, equipment::*, monster::MonsterKind};
+use crate::{
+    accessory::Accessory, consumable::*, equipment::*, item::Item, material::Material,
+    monster::MonsterKind, utils::*,
+};
 use rand::Rng;
+use regex::Regex;
+use std::{
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
 
 use MonsterKind::*;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Loot {
     pub(crate) item: Consumable,
     pub(crate) amount: usize,
-    pub(crate) gear: Option<Gear>,
+    pub(crate) gear: Option<Piece>,
+    pub(crate) material: Option<(Material, usize)>,
+    pub(crate) accessory: Option<Accessory>,
 }
 
 impl Loot {
@@ -16,24 +29,68 @@ impl Loot {
     //     let amount = rng.gen_range(0..3);
     //     Self { item, amount }
     // }
+    /// Materials and accessories are weightless.
+    pub fn weight(&self) -> usize {
+        self.item.weight() * self.amount + self.gear.as_ref().map_or(0, Piece::weight)
+    }
+    /// Split into the weightless materials and accessory, and the rest.
+    pub fn split_weightless(self) -> (Self, Self) {
+        let rest = Self {
+            material: None,
+            accessory: None,
+            ..self.clone()
+        };
+        let weightless = Self {
+            amount: 0,
+            gear: None,
+            ..self
+        };
+        (weightless, rest)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.amount == 0
+            && self.gear.is_none()
+            && self.material.is_none()
+            && self.accessory.is_none()
+    }
     pub fn announce(&self) {
         match self.amount {
             0 => (),
             1 => println!("You found a {}!", self.item),
             x => println!("You found {} {}s!", x, self.item),
         }
+        match self.material {
+            Some((x, 1)) => println!("You gathered a {x}!"),
+            Some((x, n)) => println!("You gathered {n} {}!", x.plural().rgb(0xcd, 0x85, 0x3f)),
+            None => (),
+        }
+        if let Some(x) = self.accessory {
+            println!("You found a {x}!");
+        }
         if let Some(ref x) = self.gear {
-            println!("You found a {x}!")
+            match x.affixes.as_slice() {
+                [] => println!("You found a {} {x}!", x.rarity),
+                affixes => {
+                    let affixes: Vec<_> = affixes.iter().map(|x| x.to_string()).collect();
+                    println!("You found a {} {x} ({})!", x.rarity, affixes.join(", "))
+                }
+            }
         }
     }
-    pub(crate) fn gen_imp<T: Rng>(rng: &mut T, kind: MonsterKind) -> Self {
+    /// Each point of `luck` raises the maximum amount of consumables by one
+    /// and the chance of finding gear by 5%. The rarity and affixes of gear
+    /// scale with the monster's `level`. Only bosses drop accessories.
+    pub(crate) fn gen_imp<T: Rng>(rng: &mut T, kind: MonsterKind, level: usize, luck: i64) -> Self {
         let item = Consumable::gen(rng);
+        let luck = luck.max(0) as usize;
         let (amount, gear) = match kind {
             Fairy => (0, None),
             _ => {
-                let amount = rng.gen_range(0..kind.loot_weight());
-                let gear = if rng.gen_bool(kind.loot_prob()) {
-                    Some(Gear::from_index_trunc(rng.gen_range(0u8..=12u8)))
+                let amount = rng.gen_range(0..kind.loot_weight() + luck);
+                let prob = (kind.loot_prob() * (1.0 + 0.05 * luck as f64)).min(1.0);
+                let gear = if rng.gen_bool(prob) {
+                    let gear = Gear::from_index_trunc(rng.gen_range(0u8..=12u8));
+                    Some(Piece::gen(rng, gear, level))
                 } else {
                     None
                 };
@@ -41,17 +98,152 @@ impl Loot {
             }
         };
 
-        Self { item, amount, gear }
+        let material = if rng.gen_bool(0.6) {
+            Some((Material::from_monster(kind), rng.gen_range(1..=2)))
+        } else {
+            None
+        };
+
+        let accessory = if kind.is_boss() && rng.gen_bool((0.1 + 0.01 * luck as f64).min(1.0)) {
+            Some(Accessory::gen(rng))
+        } else {
+            None
+        };
+
+        Self {
+            item,
+            amount,
+            gear,
+            material,
+            accessory,
+        }
     }
     pub fn gen<T: Rng>(rng: &mut T) -> Self {
         let kind = MonsterKind::gen(rng);
-        Self::gen_imp(rng, kind)
+        Self::gen_imp(rng, kind, 1, 0)
     }
-    pub fn rand_weighted(kind: MonsterKind) -> Self {
-        Self::gen_imp(&mut rand::thread_rng(), kind)
+    pub fn rand_weighted(kind: MonsterKind, level: usize, luck: i64) -> Self {
+        Self::gen_imp(&mut rand::thread_rng(), kind, level, luck)
     }
 
     pub fn rand() -> Self {
         Self::gen(&mut rand::thread_rng())
     }
 }
+
+/// What to do when loot does not fit in the player's bags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickupTransaction {
+    Drop { item: Item, count: usize },
+    Take,
+    Leave,
+}
+
+impl FromStr for PickupTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_DROP: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:drop|d)$").unwrap());
+        static RE_TAKE: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:take|t)$").unwrap());
+        static RE_LEAVE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:leave|l)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.split_once(' ') {
+            if RE_DROP.is_match(lhs) {
+                let rhs = rhs.trim();
+                if let Ok(item) = rhs.parse::<Item>() {
+                    return Ok(Self::Drop { item, count: 1 });
+                } else if let Some((n, item)) = rhs.split_once(' ') {
+                    if let (Ok(count), Ok(item)) = (n.parse::<usize>(), item.parse::<Item>()) {
+                        return Ok(Self::Drop { item, count });
+                    }
+                }
+            }
+        } else if RE_TAKE.is_match(s) {
+            return Ok(Self::Take);
+        } else if RE_LEAVE.is_match(s) || is_quit(s) {
+            return Ok(Self::Leave);
+        }
+        Err(s.to_string())
+    }
+}
+
+pub(crate) fn pickup_menu(msg: &str) -> PickupTransaction {
+    let mut buf = String::with_capacity(1 << 7);
+    println!("---- Your bags are too heavy to carry the loot! ----");
+    println!("{}", msg);
+    println!("e.g. `drop 2 food`; `take` anyway and be slowed, or `leave` it behind");
+    let n = msg.lines().count() + 3;
+    loop {
+        String::clear(&mut buf);
+        print!("🎒 ");
+        io::Write::flush(&mut io::stdout()).unwrap();
+
+        let stdin = io::stdin();
+        let mut handle = stdin.lock();
+        match handle.read_line(&mut buf) {
+            Ok(_) => {
+                let _ = crate::readline::clear_last_n_lines(1);
+            }
+            Err(e) => println!("Error in pickup menu readline: {:#?}", e),
+        }
+        if let Ok(transaction) = buf.parse::<PickupTransaction>() {
+            let _ = crate::readline::clear_last_n_lines(n);
+            break transaction;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "drop 2 food".parse::<PickupTransaction>().unwrap(),
+            PickupTransaction::Drop {
+                item: Item::from(Food),
+                count: 2
+            }
+        );
+        assert_eq!(
+            "d axe".parse::<PickupTransaction>().unwrap(),
+            PickupTransaction::Drop {
+                item: Item::from(Axe),
+                count: 1
+            }
+        );
+        assert_eq!(
+            "t".parse::<PickupTransaction>().unwrap(),
+            PickupTransaction::Take
+        );
+        assert_eq!(
+            "leave".parse::<PickupTransaction>().unwrap(),
+            PickupTransaction::Leave
+        );
+    }
+
+    #[test]
+    fn weightless() {
+        let mut player = crate::player::Player::new(crate::class::Class::Warrior);
+        player.equipment_bag.push_multiple(Piece::from(Axe), 100);
+        assert!(player.is_overloaded());
+        let material = Material::from_monster(Wolf);
+        let loot = Loot {
+            item: Food,
+            amount: 2,
+            gear: None,
+            material: Some((material, 2)),
+            accessory: None,
+        };
+        let weight = loot.weight();
+        assert!(weight > 0);
+        let (loot, rest) = loot.split_weightless();
+        assert_eq!((loot.weight(), rest.weight()), (0, weight));
+        assert_eq!(rest.material, None);
+        // Weightless loot is taken without prompting, however overloaded
+        assert_eq!(player.pick_up(loot), None);
+        assert_eq!(player.materials.n_available(&material), 2);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{
+    consumable::*,
+    equipment::{Gear::*, Rarity},
+    player::Player,
+    trade::*,
+    utils::is_quit,
+};
+use regex::Regex;
+use std::{
+    fmt,
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Shop {
+    Apothecary,
+    Armoury,
+    MagicShop,
+    TravellingMerchant,
+}
+use Shop::*;
+
+impl Shop {
+    pub(crate) const fn all() -> [Shop; 4] {
+        [Apothecary, Armoury, MagicShop, TravellingMerchant]
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Apothecary => "Potions, food and remedies",
+            Armoury => "Weapons, armor and shields",
+            MagicShop => "Scrolls, bombs and wizard's gear",
+            TravellingMerchant => "Rare gear at a steep price; only in town on some days",
+        }
+    }
+    pub const fn catalogue(&self) -> Catalogue {
+        match self {
+            Apothecary => Catalogue {
+                name: "apothecary",
+                consumables: &[
+                    (HealthPotion, 10),
+                    (ManaPotion, 10),
+                    (Food, 20),
+                    (Antidote, 5),
+                    (TpTonic, 5),
+                    (Elixir, 1),
+                ],
+                gear: &[],
+                gear_count: 0,
+                rarity: Rarity::Common,
+                pricing: Pricing::DEFAULT,
+                presence: 100,
+            },
+            Armoury => Catalogue {
+                name: "armoury",
+                consumables: &[],
+                gear: &[Sword, Axe, Helmet, Breastplate, Greaves, Gauntlet, Shield],
+                gear_count: 5,
+                rarity: Rarity::Common,
+                pricing: Pricing::DEFAULT,
+                presence: 100,
+            },
+            MagicShop => Catalogue {
+                name: "magic shop",
+                consumables: &[
+                    (ReturnScroll, 3),
+                    (SmokeBomb, 3),
+                    (Bomb, 3),
+                    (ManaPotion, 5),
+                ],
+                gear: &[Wand, Staff, Hat, Robe, Breeches, Glove],
+                gear_count: 4,
+                rarity: Rarity::Common,
+                pricing: Pricing {
+                    markup: 35,
+                    ..Pricing::DEFAULT
+                },
+                presence: 100,
+            },
+            TravellingMerchant => Catalogue {
+                name: "travelling merchant",
+                consumables: &[(Elixir, 2)],
+                gear: &[
+                    Sword,
+                    Axe,
+                    Wand,
+                    Staff,
+                    Helmet,
+                    Hat,
+                    Breastplate,
+                    Robe,
+                    Greaves,
+                    Breeches,
+                    Gauntlet,
+                    Glove,
+                    Shield,
+                ],
+                gear_count: 3,
+                rarity: Rarity::Rare,
+                pricing: Pricing {
+                    markup: 60,
+                    buyback: 60,
+                    ..Pricing::DEFAULT
+                },
+                presence: 30,
+            },
+        }
+    }
+    pub(crate) fn print_menu_item(&self) {
+        println!(
+            "    {:<30} | {:<30}",
+            format!("{}", self),
+            self.description(),
+        );
+    }
+}
+
+impl fmt::Display for Shop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Apothecary => write!(f, "{}othecary", "Ap".bold().underline()),
+            Armoury => write!(f, "{}moury", "Ar".bold().underline()),
+            MagicShop => write!(f, "{}agic shop", "M".bold().underline()),
+            TravellingMerchant => write!(f, "{}ravelling merchant", "T".bold().underline()),
+        }
+    }
+}
+
+impl FromStr for Shop {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        static RE_APOTHECARY: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:apothecary|ap)$").unwrap());
+        static RE_ARMOURY: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:armou?ry|ar)$").unwrap());
+        static RE_MAGIC: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:magic(?: shop)?|m)$").unwrap());
+        static RE_TRAVELLING: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:travell?ing(?: merchant)?|merchant|t)$").unwrap());
+
+        if RE_APOTHECARY.is_match(s) {
+            Ok(Apothecary)
+        } else if RE_ARMOURY.is_match(s) {
+            Ok(Armoury)
+        } else if RE_MAGIC.is_match(s) {
+            Ok(MagicShop)
+        } else if RE_TRAVELLING.is_match(s) {
+            Ok(TravellingMerchant)
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
+/// The town's shops, one merchant for each.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Market {
+    merchants: Vec<(Shop, Merchant)>,
+}
+
+impl Market {
+    pub fn new() -> Self {
+        Self {
+            merchants: Shop::all()
+                .into_iter()
+                .map(|shop| (shop, Merchant::new(shop.catalogue())))
+                .collect(),
+        }
+    }
+    pub fn get_mut(&mut self, shop: Shop) -> Option<&mut Merchant> {
+        self.merchants
+            .iter_mut()
+            .find(|(x, _)| *x == shop)
+            .map(|(_, merchant)| merchant)
+    }
+    pub(crate) fn open_shops(&self) -> impl Iterator<Item = Shop> + '_ {
+        self.merchants
+            .iter()
+            .filter(|(_, merchant)| merchant.open)
+            .map(|(shop, _)| *shop)
+    }
+    /// A new day: every merchant restocks, and some come or go.
+    pub fn restock(&mut self, level: usize) {
+        for (_, merchant) in self.merchants.iter_mut() {
+            merchant.restock(level);
+        }
+    }
+    /// Returns `None` if the player leaves the market.
+    pub fn menu(&self) -> Option<Shop> {
+        let mut buf = String::with_capacity(1 << 7);
+        println!("==== Visiting the market... ====");
+        let mut n = 2;
+        for shop in self.open_shops() {
+            shop.print_menu_item();
+            n += 1;
+        }
+        println!("`q` to leave");
+        loop {
+            String::clear(&mut buf);
+            print!("🏪 ");
+            io::Write::flush(&mut io::stdout()).unwrap();
+
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            match handle.read_line(&mut buf) {
+                Ok(_) => {
+                    let _ = crate::readline::clear_last_n_lines(1);
+                }
+                Err(e) => println!("Error in market menu readline: {:#?}", e),
+            }
+            let s = buf.trim();
+            if is_quit(s) {
+                let _ = crate::readline::clear_last_n_lines(n);
+                return None;
+            } else if let Ok(shop) = s.parse::<Shop>() {
+                if self.open_shops().any(|x| x == shop) {
+                    let _ = crate::readline::clear_last_n_lines(n);
+                    return Some(shop);
+                }
+            }
+        }
+    }
+    pub fn trade(&mut self, player: &mut Player) {
+        while let Some(shop) = self.menu() {
+            if let Some(merchant) = self.get_mut(shop) {
+                merchant.trade(player);
+            }
+        }
+    }
+}
+
+impl Default for Market {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::Item;
+
+    #[test]
+    fn catalogues() {
+        assert!(Apothecary.catalogue().deals_in(&Item::from(Food)));
+        assert!(!Apothecary.catalogue().deals_in(&Item::from(Sword)));
+        assert!(Armoury.catalogue().deals_in(&Item::from(Shield)));
+        assert!(!MagicShop.catalogue().deals_in(&Item::from(Axe)));
+
+        let mut market = Market::new();
+        assert!(market.open_shops().any(|x| x == Apothecary));
+        let merchant = market.get_mut(TravellingMerchant).unwrap();
+        assert!(merchant
+            .equipment_bag
+            .pieces()
+            .all(|(x, _)| x.rarity >= Rarity::Rare));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("ap".parse::<Shop>().unwrap(), Apothecary);
+        assert_eq!("Armory".parse::<Shop>().unwrap(), Armoury);
+        assert_eq!("magic shop".parse::<Shop>().unwrap(), MagicShop);
+        assert_eq!("t".parse::<Shop>().unwrap(), TravellingMerchant);
+        assert!("a".parse::<Shop>().is_err());
+    }
+}
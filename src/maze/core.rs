@@ -1,5 +1,8 @@
 use crate::maze::element::*;
-use crate::{grid::*, monster::MonsterKind, utils::is_quit};
+use crate::{
+    dialogue::Dialogue, grid::*, inventory::Inventory, loot::Loot, monster::MonsterKind,
+    utils::is_quit,
+};
 use indexmap::IndexMap;
 use rand::Rng;
 use regex::Regex;
@@ -64,6 +67,13 @@ pub struct Maze {
     pub(crate) player: (usize, usize),
     pub(crate) monsters: IndexMap<(usize, usize), MonsterKind>,
     pub(crate) active_portals: Vec<(usize, usize)>,
+    pub(crate) corpses: IndexMap<(usize, usize), Inventory>,
+    /// Loot waiting on each treasure tile, rolled when it was placed
+    pub(crate) treasures: IndexMap<(usize, usize), Loot>,
+    /// Where monsters live, and respawn once defeated
+    pub(crate) lairs: Vec<(MonsterKind, (usize, usize))>,
+    /// Who stands where, and what they have to say
+    pub(crate) npcs: IndexMap<(usize, usize), Dialogue>,
 }
 impl Maze {
     pub fn hide_player_mark(&mut self) {
@@ -100,6 +110,10 @@ impl Maze {
             player,
             monsters: IndexMap::new(),
             active_portals: Vec::new(),
+            corpses: IndexMap::new(),
+            treasures: IndexMap::new(),
+            lairs: Vec::new(),
+            npcs: IndexMap::new(),
         }
     }
     pub(crate) fn create_portal(&mut self, src_position: (usize, usize), dst: Destination) {
@@ -113,6 +127,45 @@ impl Maze {
             }
         }
     }
+    /// Leave the items beside the player, returning `false` if there is no room.
+    pub fn place_corpse(&mut self, items: Inventory) -> bool {
+        match self.first_movement_proposal(self.player) {
+            Some(pos) => {
+                self.grid[pos] = Corpse;
+                self.corpses.insert(pos, items);
+                true
+            }
+            None => false,
+        }
+    }
+    /// Returns `false` if the tile is occupied.
+    pub fn place_npc(&mut self, pos: (usize, usize), dialogue: Dialogue) -> bool {
+        if self.grid[pos] != Empty {
+            return false;
+        }
+        self.grid[pos] = Npc;
+        self.npcs.insert(pos, dialogue);
+        true
+    }
+    /// Returns `false` if the tile is occupied.
+    pub fn place_treasure(&mut self, pos: (usize, usize), loot: Loot) -> bool {
+        if self.grid[pos] != Empty {
+            return false;
+        }
+        self.grid[pos] = Treasure;
+        self.treasures.insert(pos, loot);
+        true
+    }
+    pub fn take_treasure(&mut self, pos: (usize, usize)) -> Option<Loot> {
+        let loot = self.treasures.swap_remove(&pos)?;
+        self.grid[pos] = Empty;
+        Some(loot)
+    }
+    pub fn loot_corpse(&mut self, pos: (usize, usize)) -> Option<Inventory> {
+        let items = self.corpses.swap_remove(&pos)?;
+        self.grid[pos] = Empty;
+        Some(items)
+    }
     pub fn spawn_monster(&mut self, kind: MonsterKind, pos: (usize, usize)) -> bool {
         if self.grid.check_bounds(pos) && self.grid[pos] == Empty {
             self.grid[pos] = Monster(kind);
@@ -122,6 +175,24 @@ impl Maze {
             false
         }
     }
+    /// Spawn a monster which returns to `pos` after it is defeated.
+    pub fn add_lair(&mut self, kind: MonsterKind, pos: (usize, usize)) -> bool {
+        self.lairs.push((kind, pos));
+        self.spawn_monster(kind, pos)
+    }
+    /// Return defeated monsters to their lairs, if unoccupied. Returns the
+    /// number of monsters respawned.
+    pub fn respawn_monsters(&mut self) -> usize {
+        let mut n = 0;
+        for (kind, pos) in self.lairs.clone() {
+            let alive = self.monsters.values().filter(|x| **x == kind).count();
+            let lairs = self.lairs.iter().filter(|(x, _)| *x == kind).count();
+            if alive < lairs && self.spawn_monster(kind, pos) {
+                n += 1;
+            }
+        }
+        n
+    }
     pub fn remove_monster(&mut self, pos: (usize, usize)) -> Option<MonsterKind> {
         if self.grid.check_bounds(pos) {
             match self.grid[pos] {
@@ -168,11 +239,11 @@ impl Maze {
         grid[(2, 1)] = Tree;
         grid[(3, 2)] = Rock;
         grid[(1, 2)] = Tree;
-        grid[(8, 1)] = Treasure;
 
         grid[(0, 0)] = InactivePortal;
         grid[(8, 8)] = InactivePortal;
         grid[(10, 10)] = Dungeon;
+        grid[(19, 0)] = InactivePortal;
 
         grid[(15, 15)] = Fence;
         grid[(15, 16)] = Fence;
@@ -190,27 +261,32 @@ impl Maze {
             player,
             monsters: IndexMap::new(),
             active_portals: Vec::new(),
+            corpses: IndexMap::new(),
+            treasures: IndexMap::new(),
+            lairs: Vec::new(),
+            npcs: IndexMap::new(),
         };
 
-        maze.spawn_monster(MonsterKind::Frog, (4, 5));
-        maze.spawn_monster(MonsterKind::Bat, (4, 6));
-        maze.spawn_monster(MonsterKind::Snake, (5, 6));
-        maze.spawn_monster(MonsterKind::Wolf, (4, 7));
-        maze.spawn_monster(MonsterKind::Goblin, (4, 8));
-        maze.spawn_monster(MonsterKind::Bear, (6, 6));
-        maze.spawn_monster(MonsterKind::Undead, (15, 10));
-        maze.spawn_monster(MonsterKind::Orc, (2, 7));
-        maze.spawn_monster(MonsterKind::Vampire, (9, 9));
-        maze.spawn_monster(MonsterKind::Troll, (10, 15));
-        maze.spawn_monster(MonsterKind::Mammoth, (7, 7));
-        maze.spawn_monster(MonsterKind::Dragon, (18, 7));
+        maze.add_lair(MonsterKind::Frog, (4, 5));
+        maze.add_lair(MonsterKind::Bat, (4, 6));
+        maze.add_lair(MonsterKind::Snake, (5, 6));
+        maze.add_lair(MonsterKind::Wolf, (4, 7));
+        maze.add_lair(MonsterKind::Goblin, (4, 8));
+        maze.add_lair(MonsterKind::Bear, (6, 6));
+        maze.add_lair(MonsterKind::Undead, (15, 10));
+        maze.add_lair(MonsterKind::Orc, (2, 7));
+        maze.add_lair(MonsterKind::Vampire, (9, 9));
+        maze.add_lair(MonsterKind::Troll, (10, 15));
+        maze.add_lair(MonsterKind::Mammoth, (7, 7));
+        maze.add_lair(MonsterKind::Dragon, (18, 7));
+        maze.place_npc((2, 4), Dialogue::hermit());
+        maze.place_treasure((8, 1), Loot::rand());
         maze
     }
     pub fn new_room() -> Self {
         let mut grid = Grid::new_default(5, 5);
         let player = (1, 2);
         grid[player] = Player;
-        grid[(2, 2)] = Treasure;
         grid[(0, 0)] = Wall;
         grid[(0, 1)] = Wall;
         grid[(0, 2)] = InactivePortal;
@@ -227,12 +303,18 @@ impl Maze {
         grid[(3, 4)] = Wall;
         grid[(2, 4)] = Wall;
         grid[(1, 4)] = Wall;
-        Self {
+        let mut maze = Self {
             grid,
             player,
             monsters: IndexMap::new(),
             active_portals: Vec::new(),
-        }
+            corpses: IndexMap::new(),
+            treasures: IndexMap::new(),
+            lairs: Vec::new(),
+            npcs: IndexMap::new(),
+        };
+        maze.place_treasure((2, 2), Loot::rand());
+        maze
     }
     pub fn menu(&self) -> MazeAction {
         let mut buf = String::with_capacity(1 << 10);
@@ -385,6 +467,11 @@ impl Maze {
                     println!("It's a wall.");
                     MazeEvent::Interact(Wall, new_pos)
                 }
+                Corpse => {
+                    println!("It's your corpse!");
+                    MazeEvent::Interact(Corpse, new_pos)
+                }
+                Npc => MazeEvent::Interact(Npc, new_pos),
                 Empty => {
                     println!("There's nothing there.");
                     MazeEvent::Interact(Empty, new_pos)
@@ -481,4 +568,29 @@ mod tests {
         }
         assert_eq!(maze.grid[(5, 0)], Player);
     }
+
+    #[test]
+    fn respawn() {
+        let mut maze = Maze::new_default(10, 10);
+        maze.add_lair(MonsterKind::Wolf, (1, 1));
+        maze.add_lair(MonsterKind::Wolf, (1, 2));
+        maze.monster_movement();
+        let pos = *maze.monsters.keys().next().unwrap();
+        maze.remove_monster(pos);
+        assert_eq!(maze.respawn_monsters(), 1);
+        assert_eq!(maze.respawn_monsters(), 0);
+        assert_eq!(maze.monsters.len(), 2);
+    }
+    #[test]
+    fn treasure() {
+        let mut maze = Maze::new_room();
+        let loot = maze.treasures[&(2, 2)].clone();
+        assert_eq!(maze.grid[(2, 2)], Treasure);
+        // The loot is rolled once, not on every visit
+        assert_eq!(maze.take_treasure((2, 2)), Some(loot.clone()));
+        assert_eq!(maze.grid[(2, 2)], Empty);
+        assert_eq!(maze.take_treasure((2, 2)), None);
+        assert!(maze.place_treasure((2, 2), loot));
+        assert!(!maze.place_treasure((1, 2), Loot::rand()));
+    }
 }
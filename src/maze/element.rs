@@ -15,6 +15,8 @@ pub enum Element {
     ActivePortal(Destination),
     Fence,
     Wall,
+    Corpse,
+    Npc,
 }
 use Element::*;
 
@@ -32,6 +34,8 @@ impl Element {
             InactivePortal | ActivePortal(_) => '🪞',
             Fence => '🔶',
             Wall => '⬛',
+            Corpse => '🪦',
+            Npc => '🧙',
         }
     }
 }
@@ -61,6 +65,8 @@ impl TryFrom<char> for Element {
             '🪞' => InactivePortal,
             '🔶' => Fence,
             '⬛' => Wall,
+            '🪦' => Corpse,
+            '🧙' => Npc,
             _ => match MonsterKind::try_from(value) {
                 Ok(kind) => Monster(kind),
                 Err(_) => return Err(()),
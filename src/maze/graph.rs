@@ -80,6 +80,10 @@ impl MazeGraph {
 }
 
 impl MazeGraph {
+    /// Returns the number of monsters respawned across all mazes.
+    pub fn respawn_monsters(&mut self) -> usize {
+        self.0.iter_mut().map(Maze::respawn_monsters).sum()
+    }
     pub fn new_demo() -> Self {
         let starting_area = Maze::new_demo();
         let room1 = Maze::new_room();
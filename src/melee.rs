@@ -1,4 +1,4 @@
-use crate::resource::*;
+use crate::{resource::*, skill::SkillTree};
 use regex::Regex;
 use std::{
     fmt,
@@ -81,7 +81,7 @@ impl fmt::Display for Melee {
     }
 }
 
-pub(crate) fn melee_menu(strength: i64) -> Option<MeleeAttack> {
+pub(crate) fn melee_menu(strength: i64, skills: &SkillTree) -> Option<MeleeAttack> {
     const N: usize = 5;
     let mut buf = String::with_capacity(1 << 7);
     println!("---- Entering melee menu... ----");
@@ -91,9 +91,9 @@ pub(crate) fn melee_menu(strength: i64) -> Option<MeleeAttack> {
         "cost".underline(),
         "gain".underline(),
     );
-    let basic = MeleeAttack::new(Basic, strength);
-    let power = MeleeAttack::new(Power, strength);
-    let sup = MeleeAttack::new(Super, strength);
+    let basic = MeleeAttack::with_skills(Basic, strength, skills);
+    let power = MeleeAttack::with_skills(Power, strength, skills);
+    let sup = MeleeAttack::with_skills(Super, strength, skills);
     basic.print_menu_item();
     power.print_menu_item();
     sup.print_menu_item();
@@ -130,24 +130,130 @@ pub(crate) fn melee_menu(strength: i64) -> Option<MeleeAttack> {
     }
 }
 
+/// A sequence of melee attacks over consecutive turns which grants
+/// a bonus when completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combo {
+    /// Basic → Basic → Power: the Power attack deals 50% more damage
+    Crescendo,
+    /// Basic → Basic → Basic: restores 15 TP
+    Flurry,
+    /// Power → Super: the Super attack stuns the monster
+    Crush,
+}
+pub use Combo::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComboEffect {
+    /// Percentage of bonus damage
+    Damage(i64),
+    /// Amount of TP restored
+    Technical(i64),
+    Stun,
+}
+
+impl Combo {
+    pub const fn sequence(&self) -> &'static [Melee] {
+        match self {
+            Crescendo => &[Basic, Basic, Power],
+            Flurry => &[Basic, Basic, Basic],
+            Crush => &[Power, Super],
+        }
+    }
+    pub const fn effect(&self) -> ComboEffect {
+        match self {
+            Crescendo => ComboEffect::Damage(50),
+            Flurry => ComboEffect::Technical(15),
+            Crush => ComboEffect::Stun,
+        }
+    }
+    pub(crate) const fn all() -> [Combo; 3] {
+        [Crescendo, Flurry, Crush]
+    }
+}
+
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Crescendo => write!(f, "{}", "Crescendo".rgb(0xda, 0xa5, 0x20).bold()),
+            Flurry => write!(f, "{}", "Flurry".rgb(0xda, 0xa5, 0x20).bold()),
+            Crush => write!(f, "{}", "Crush".rgb(0xda, 0xa5, 0x20).bold()),
+        }
+    }
+}
+
+/// Tracks the melee attacks performed on consecutive turns.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ComboCounter {
+    count: usize,
+    chain: Vec<Melee>,
+}
+
+impl ComboCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    pub fn reset(&mut self) {
+        self.count = 0;
+        Vec::clear(&mut self.chain);
+    }
+    /// Record a melee attack, returning the combo which it completes, if any.
+    /// Completing a combo starts a new chain, but the counter keeps going.
+    pub fn push(&mut self, kind: Melee) -> Option<Combo> {
+        self.count += 1;
+        self.chain.push(kind);
+        let combo = Combo::all()
+            .into_iter()
+            .find(|combo| self.chain.ends_with(combo.sequence()));
+        if combo.is_some() {
+            Vec::clear(&mut self.chain);
+        }
+        combo
+    }
+}
+
+impl fmt::Display for ComboCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[x{}]", "Combo".bold().yellow(), self.count)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MeleeAttack {
     pub(crate) kind: Melee,
     pub(crate) damage: i64,
+    pub(crate) cost: i64,
 }
 impl MeleeAttack {
     pub fn new(kind: Melee, strength: i64) -> Self {
         Self {
             kind,
             damage: strength * kind.damage(),
+            cost: kind.cost(),
         }
     }
+    pub fn with_skills(kind: Melee, strength: i64, skills: &SkillTree) -> Self {
+        let mut melee = Self::new(kind, strength);
+        melee.cost = (melee.cost - skills.melee_discount(kind)).max(0);
+        melee
+    }
     pub const fn cost(&self) -> i64 {
-        self.kind.cost()
+        self.cost
     }
     pub const fn gain(&self) -> i64 {
         self.kind.gain()
     }
+    /// Whether a monster's attack may stun the player, who then loses their
+    /// next turn. The player's own attacks stun only through `Crush`.
+    pub const fn stuns(&self) -> bool {
+        matches!(self.kind, Super)
+    }
+    pub fn apply_bonus(&mut self, pct: i64) {
+        self.damage += (self.damage * pct) / 100;
+    }
     pub(crate) fn print_menu_item(&self) {
         println!(
             "    {:>width$} |  {:>6}   | {:>2} {} | {:>2} {}",
@@ -161,3 +267,28 @@ impl MeleeAttack {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combo() {
+        let mut c = ComboCounter::new();
+        assert_eq!(c.push(Basic), None);
+        assert_eq!(c.push(Basic), None);
+        assert_eq!(c.push(Power), Some(Crescendo));
+        assert_eq!(c.push(Super), None);
+        assert_eq!(c.count(), 4);
+
+        c.reset();
+        assert_eq!(c.push(Power), None);
+        assert_eq!(c.push(Super), Some(Crush));
+        assert_eq!(c.push(Basic), None);
+        assert_eq!(c.push(Basic), None);
+        assert_eq!(c.push(Basic), Some(Flurry));
+        assert_eq!(c.count(), 5);
+        c.reset();
+        assert_eq!(c.count(), 0);
+    }
+}
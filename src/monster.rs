@@ -1,6 +1,6 @@
-use crate::{melee::*, resource::*, spell::*};
+use crate::{class::Ability, melee::*, resource::*, spell::*};
 use rand::Rng;
-use std::{convert::TryFrom, fmt, hash::Hash};
+use std::{convert::TryFrom, fmt, hash::Hash, str::FromStr};
 use yansi::{Paint, Painted};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -37,9 +37,12 @@ impl Monster {
         buf
     }
 
+    /// A monster between half of `level` and `level`, so that the range
+    /// scales with the player regardless of the level cap.
     pub fn rand_level(kind: MonsterKind, level: usize) -> Self {
         let mut rng = rand::thread_rng();
-        Self::new(kind, rng.gen_range(1usize..=level.min(10usize)))
+        let level = level.max(1);
+        Self::new(kind, rng.gen_range(level.div_ceil(2)..=level))
     }
     pub fn rand(level: usize) -> Self {
         Self::rand_level(MonsterKind::rand(), level)
@@ -61,6 +64,17 @@ impl Monster {
         );
         self.receive_damage(amount);
     }
+    /// Scale a weapon enchantment's damage by the monster's weakness or
+    /// resistance.
+    pub fn elemental_damage(&self, element: Offense, amount: i64) -> i64 {
+        let pct = self.kind.elemental_pct(element);
+        if pct > 100 {
+            println!("The {} is weak to {element}!", self.kind);
+        } else if pct < 100 {
+            println!("The {} resists {element}!", self.kind);
+        }
+        amount * pct / 100
+    }
     pub fn receive_spell_attack(&mut self, spell: OffenseSpell) {
         let amount = spell.damage;
         let kind = spell.kind;
@@ -71,6 +85,21 @@ impl Monster {
         );
         self.receive_damage(amount);
     }
+    pub fn receive_ability_attack(&mut self, ability: Ability, amount: i64) {
+        println!(
+            "Your {ability} hits the {} for {} damage!",
+            self.kind,
+            amount.magenta(),
+        );
+        self.receive_damage(amount);
+    }
+    /// The gold which a Rogue may steal from the monster.
+    pub fn purse(&self) -> usize {
+        match self.kind {
+            MonsterKind::Fairy => 0,
+            kind => kind.strength() as usize * 2 * self.level,
+        }
+    }
     pub(crate) fn cast_melee(&mut self, melee: Melee) -> MeleeAttack {
         let cost = melee.cost();
         let gain = melee.gain();
@@ -143,6 +172,24 @@ impl MonsterKind {
             Fairy => -20,
         }
     }
+    /// Percentage of elemental damage received from `element`.
+    pub const fn elemental_pct(&self, element: Offense) -> i64 {
+        match (self, element) {
+            (Wolf | Bear | Undead | Vampire | Troll | Mammoth, Offense::Fire) => 150,
+            (Bat | Fairy | Dragon, Offense::Stone) => 150,
+            (Frog | Snake | Dragon, Offense::Fire) => 50,
+            (Goblin | Orc, Offense::Stone) => 50,
+            _ => 100,
+        }
+    }
+    /// Whether the monster's melee attacks may poison.
+    pub const fn is_venomous(&self) -> bool {
+        matches!(self, Snake | Undead)
+    }
+    /// Bosses are the only monsters which drop accessories.
+    pub const fn is_boss(&self) -> bool {
+        matches!(self, Troll | Mammoth | Dragon)
+    }
     pub(crate) const fn loot_weight(&self) -> usize {
         (self.max_hp() / 20) as usize
     }
@@ -290,6 +337,19 @@ impl fmt::Display for MonsterKind {
     }
 }
 
+impl FromStr for MonsterKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        (0..Self::COUNT)
+            .map(Self::from_index)
+            .find(|kind| {
+                s.eq_ignore_ascii_case(kind.singular()) || s.eq_ignore_ascii_case(kind.plural())
+            })
+            .ok_or_else(|| s.to_string())
+    }
+}
+
 impl TryFrom<char> for MonsterKind {
     type Error = ();
     fn try_from(value: char) -> Result<Self, Self::Error> {
@@ -2,12 +2,12 @@ use indexmap::{map::Entry, IndexMap};
 use std::{fmt, hash::Hash};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct MultiSet<T: fmt::Debug + Clone + Copy + PartialEq + Eq + PartialOrd + Ord + Hash> {
+pub struct MultiSet<T: fmt::Debug + Clone + PartialEq + Eq + PartialOrd + Ord + Hash> {
     pub(crate) bag: IndexMap<T, usize>,
     pub(crate) sum: usize,
 }
 
-impl<T: fmt::Debug + Clone + Copy + PartialEq + Eq + PartialOrd + Ord + Hash> MultiSet<T> {
+impl<T: fmt::Debug + Clone + PartialEq + Eq + PartialOrd + Ord + Hash> MultiSet<T> {
     pub fn with_capacity(n: usize) -> Self {
         Self {
             bag: IndexMap::with_capacity(n),
@@ -26,7 +26,7 @@ impl<T: fmt::Debug + Clone + Copy + PartialEq + Eq + PartialOrd + Ord + Hash> Mu
     }
 
     pub fn pop(&mut self, item: T) -> Option<T> {
-        match self.bag.entry(item) {
+        match self.bag.entry(item.clone()) {
             Entry::Occupied(mut v) => {
                 if *v.get() > 0 {
                     self.sum -= 1;
@@ -40,7 +40,7 @@ impl<T: fmt::Debug + Clone + Copy + PartialEq + Eq + PartialOrd + Ord + Hash> Mu
         }
     }
     pub fn pop_multiple(&mut self, item: T, n: usize) -> Option<(T, usize)> {
-        match self.bag.entry(item) {
+        match self.bag.entry(item.clone()) {
             Entry::Occupied(mut v) => match *v.get() {
                 0 => None,
                 u if u >= n => {
@@ -83,9 +83,21 @@ impl<T: fmt::Debug + Clone + Copy + PartialEq + Eq + PartialOrd + Ord + Hash> Mu
     pub fn n_total(&self) -> usize {
         self.sum
     }
+    /// Whether every item of `other` is available in at least the same count.
+    pub fn contains_all(&self, other: &Self) -> bool {
+        other
+            .bag
+            .iter()
+            .all(|(item, count)| self.n_available(item) >= *count)
+    }
+    pub fn remove_all(&mut self, other: &Self) {
+        for (item, count) in other.bag.iter() {
+            self.remove_multiple(item.clone(), *count);
+        }
+    }
 }
-impl<T: fmt::Debug + Clone + Copy + PartialEq + Eq + PartialOrd + Ord + Hash>
-    FromIterator<(T, usize)> for MultiSet<T>
+impl<T: fmt::Debug + Clone + PartialEq + Eq + PartialOrd + Ord + Hash> FromIterator<(T, usize)>
+    for MultiSet<T>
 {
     fn from_iter<I>(iter: I) -> Self
     where
@@ -156,6 +168,16 @@ mod tests {
         assert_eq!(s.n_available(&'b'), 0);
     }
 
+    #[test]
+    fn contains_all() {
+        let mut s = example();
+        let t: MultiSet<char> = [('b', 2), ('c', 1)].into_iter().collect();
+        assert!(s.contains_all(&t));
+        s.remove_all(&t);
+        assert_eq!(s.n_total(), 3);
+        assert!(!s.contains_all(&t));
+    }
+
     #[test]
     fn clear() {
         let mut s = example();
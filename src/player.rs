@@ -1,84 +1,206 @@
 use crate::{
-    consumable::*, equipment::*, inventory::*, item::equipment_bag::*, item::*, loot::Loot,
-    melee::*, monster::*, resource::*, spell::*, trade::*,
+    accessory::*, attribute::*, bank::Bank, class::*, consumable::*, craft::*, death::DeathRules,
+    equipment::*, experience::XpCurve, forge::*, inn::Room, inventory::*, item::equipment_bag::*,
+    item::*, loot::*, material::MaterialBag, melee::*, monster::*, multiset::MultiSet,
+    quest::Journal, resource::*, scoreboard::Scoreboard, skill::*, spell::*, trade::*,
+    utils::confirm,
+};
+use rand::Rng;
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Write},
 };
-use std::fmt::{self, Write};
 use yansi::{Paint, Painted};
 
 pub(crate) const PLAYER_GOLD: usize = 25;
-pub(crate) const PLAYER_STRENGTH: i64 = 1;
-pub(crate) const PLAYER_INTELLECT: i64 = 1;
 pub(crate) const PLAYER_LEVEL: usize = 1;
 pub(crate) const PLAYER_XP: usize = 0;
+/// Maximum HP granted by each point of vitality.
+pub(crate) const VITALITY_HP: i64 = 10;
+/// Maximum MP granted by each point of wisdom.
+pub(crate) const WISDOM_MP: i64 = 5;
+/// Carry capacity before strength is taken into account.
+pub(crate) const BASE_CAPACITY: i64 = 40;
+/// Carry capacity granted by each point of strength.
+pub(crate) const CAPACITY_PER_STRENGTH: i64 = 5;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Player {
+    pub(crate) class: Class,
     pub(crate) hp: Health,
     pub(crate) mp: Mana,
     pub(crate) tp: Technical,
+    pub(crate) poisoned: bool,
     pub(crate) inventory: Inventory,
     pub(crate) gold: usize,
-    pub(crate) strength: i64,
-    pub(crate) intellect: i64,
+    /// Earned by trading; merchants reward it with better prices
+    pub(crate) reputation: usize,
+    pub(crate) attributes: Attributes,
+    pub(crate) attribute_points: usize,
+    pub(crate) skills: SkillTree,
+    pub(crate) skill_points: usize,
     pub(crate) equipment: Equipment,
     pub(crate) equipment_bag: EquipmentBag,
+    pub(crate) materials: MaterialBag,
+    /// Accessories which are not being worn
+    pub(crate) accessories: MultiSet<Accessory>,
+    /// Items to be sold with `sell junk`
+    pub(crate) junk: BTreeSet<Item>,
+    /// Savings and stash, kept in town
+    pub(crate) bank: Bank,
+    /// Whether the `PhoenixAmulet` has revived the player today
+    pub(crate) phoenix_spent: bool,
+    /// Bonus experience, in percent, from a good night's sleep
+    pub(crate) rested_xp_pct: usize,
+    /// Every monster defeated
+    pub(crate) scoreboard: Scoreboard,
+    pub(crate) journal: Journal,
+    /// Set by conversations, so that people remember what passed between them
+    pub(crate) flags: BTreeSet<String>,
     pub(crate) level: usize,
     pub(crate) xp: usize,
-}
-// const R: f64 = 18466.496523378733; // -12800.0 / (0.5_f64).ln();
-// const R: f64 = 9233.248261689367; // -6400.0 / (0.5_f64).ln();
-// const R: f64 = 4616.624130844683; // -3200.0 / (0.5_f64).ln();
-const R: f64 = 2308.3120654223417; // -1600.0 / (0.5_f64).ln();
-pub(crate) fn level(x: usize) -> usize {
-    (10.0 * (1.0 - (-(x as f64) / R).exp())).ceil().max(1.0) as usize
-}
-pub(crate) fn max_xp_at_level(level: usize) -> usize {
-    let y = (level as f64) / 10.0;
-    (-((1.0 - y).ln()) * R).floor() as usize
-}
-
-pub(crate) fn xp_to_next_level(x: usize) -> usize {
-    let level = level(x);
-    if level == 10 {
-        0
-    } else {
-        let thresh = max_xp_at_level(level);
-        thresh - x + 1
-    }
+    pub(crate) curve: XpCurve,
+    pub(crate) rules: DeathRules,
 }
 const LEVEL: Painted<&'static str> = Painted::new("Level").bold().underline();
 
 impl Player {
-    pub fn new() -> Self {
+    pub fn new(class: Class) -> Self {
+        Self::with_curve(class, XpCurve::default())
+    }
+    pub fn with_curve(class: Class, curve: XpCurve) -> Self {
         Self {
-            hp: Health::default(),
-            mp: Mana::default(),
+            class,
+            hp: Health::new(class.max_hp(PLAYER_LEVEL)),
+            mp: Mana::new(class.max_mp(PLAYER_LEVEL)),
             tp: Technical::default(),
+            poisoned: false,
             inventory: Inventory::new_player(),
             gold: PLAYER_GOLD,
-            strength: PLAYER_STRENGTH,
-            intellect: PLAYER_INTELLECT,
+            reputation: 0,
+            attributes: Attributes::new(
+                class.strength(PLAYER_LEVEL),
+                class.intellect(PLAYER_LEVEL),
+            ),
+            attribute_points: 0,
+            skills: SkillTree::new(),
+            skill_points: 0,
             equipment: Equipment::default(),
-            equipment_bag: EquipmentBag::new_player(),
+            equipment_bag: class.starting_gear(),
+            materials: MaterialBag::new(),
+            accessories: MultiSet::with_capacity(Accessory::total_variants()),
+            junk: BTreeSet::new(),
+            bank: Bank::new(),
+            phoenix_spent: false,
+            rested_xp_pct: 0,
+            scoreboard: Scoreboard::new(),
+            journal: Journal::default(),
+            flags: BTreeSet::new(),
             level: PLAYER_LEVEL,
             xp: PLAYER_XP,
+            curve,
+            rules: DeathRules::default(),
         }
     }
     pub(crate) fn update_level(&mut self) {
-        let new_level = level(self.xp);
+        let new_level = self.curve.level(self.xp);
         if self.level < new_level {
             println!("You are now {} {}!", LEVEL, new_level);
+            let class = self.class;
+            self.attributes.add(
+                Strength,
+                class.strength(new_level) - class.strength(self.level),
+            );
+            self.attributes.add(
+                Intellect,
+                class.intellect(new_level) - class.intellect(self.level),
+            );
+            self.attribute_points += (new_level - self.level) * POINTS_PER_LEVEL;
+            self.skill_points += (new_level - self.level) * SKILL_POINTS_PER_LEVEL;
             self.level = new_level;
-            let new_hp = PLAYER_HP * self.level as i64;
-            self.hp = Health::new(new_hp);
-            self.mp.restore_all();
-            self.strength = PLAYER_STRENGTH * self.level as i64;
-            self.intellect = PLAYER_INTELLECT * self.level as i64;
+            self.hp = Health::new(self.max_hp());
+            self.mp = Mana::new(self.max_mp());
+            self.allocate_attributes();
+        }
+    }
+    pub fn max_hp(&self) -> i64 {
+        self.class.max_hp(self.level)
+            + self.attributes.vitality * VITALITY_HP
+            + self.equipment.max_hp()
+    }
+    pub fn max_mp(&self) -> i64 {
+        self.class.max_mp(self.level) + self.attributes.wisdom * WISDOM_MP
+    }
+    /// MP regenerated at the end of each combat round.
+    pub fn mp_regen(&self) -> i64 {
+        self.attributes.wisdom / 2
+    }
+    /// Percent chance to evade a monster's melee attack.
+    pub fn evasion(&self) -> i64 {
+        (2 * self.attributes.agility).min(50)
+    }
+    /// Percent chance for a melee attack to deal double damage.
+    pub fn critical_chance(&self) -> i64 {
+        (2 * self.attributes.luck).min(50)
+    }
+    pub fn luck(&self) -> i64 {
+        self.attributes.luck
+    }
+    pub fn regenerate(&mut self) {
+        self.restore_mp(self.mp_regen());
+    }
+    /// Effects of the player's accessories upon each step through a maze.
+    pub fn step(&mut self) {
+        self.restore_mp(self.equipment.step_mp());
+    }
+    /// Award experience, including any bonus from accessories and rest, and
+    /// return the amount gained.
+    pub fn gain_xp(&mut self, xp: usize) -> usize {
+        let xp = xp + xp * (self.equipment.xp_bonus() + self.rested_xp_pct) / 100;
+        self.xp += xp;
+        self.update_level();
+        xp
+    }
+    pub fn roll_critical(&self, melee: &mut MeleeAttack) {
+        if rand::thread_rng().gen_range(0..100) < self.critical_chance() {
+            println!("{}", "Critical hit!".bold().yellow());
+            melee.apply_bonus(100);
+        }
+    }
+    pub fn spend_attribute_points(&mut self, attr: Attribute, count: usize) -> bool {
+        if count > self.attribute_points {
+            return false;
+        }
+        self.attribute_points -= count;
+        self.attributes.add(attr, count as i64);
+        match attr {
+            Vitality => self.hp.set_max(self.max_hp()),
+            Wisdom => self.mp.set_max(self.max_mp()),
+            _ => (),
+        }
+        true
+    }
+    pub fn allocate_attributes(&mut self) {
+        while self.attribute_points > 0 {
+            match allocation_menu(&self.attributes, self.attribute_points) {
+                AllocationTransaction::Spend { attr, count } => {
+                    if !self.spend_attribute_points(attr, count) {
+                        println!("Insufficient attribute points!");
+                    }
+                }
+                AllocationTransaction::Quit => break,
+            }
         }
     }
     pub(crate) fn level(&self) -> usize {
         self.level
     }
+    pub fn xp_to_next_level(&self) -> usize {
+        self.curve.xp_to_next_level(self.xp)
+    }
+    pub fn class(&self) -> Class {
+        self.class
+    }
     pub fn restore_hp(&mut self, amount: i64) {
         self.hp.restore(amount);
     }
@@ -89,10 +211,10 @@ impl Player {
         self.tp.restore(amount);
     }
     pub fn strength(&self) -> i64 {
-        self.strength + self.equipment.strength()
+        self.attributes.strength + self.equipment.strength()
     }
     pub fn intellect(&self) -> i64 {
-        self.intellect + self.equipment.intellect()
+        self.attributes.intellect + self.equipment.intellect()
     }
     pub fn armor(&self) -> i64 {
         self.equipment.armor()
@@ -104,16 +226,59 @@ impl Player {
         self.hp.restore_all();
         self.mp.restore_all();
         self.tp.drain_all();
+        self.poisoned = false;
+    }
+    /// Revive a fallen player if they wear an unspent `PhoenixAmulet`.
+    pub fn phoenix_revive(&mut self) -> bool {
+        if self.is_alive() || self.phoenix_spent || !self.equipment.wears(PhoenixAmulet) {
+            return false;
+        }
+        self.phoenix_spent = true;
+        self.poisoned = false;
+        self.restore_hp(self.hp.pct_max(PHOENIX_HP_PCT).max(1));
+        println!("Your {} flares and you rise from the ashes!", PhoenixAmulet);
+        true
+    }
+    /// Damage dealt by poison at the end of each combat round.
+    pub fn poison_damage(&self) -> i64 {
+        self.hp.pct_max(5).max(1)
+    }
+    pub fn end_round(&mut self) {
+        if self.poisoned {
+            let amount = self.poison_damage();
+            println!("The poison deals {} damage!", amount.magenta());
+            self.receive_damage(amount);
+        }
+        self.regenerate();
+    }
+    pub fn apply_death_penalty(&mut self) {
+        let gold = self.gold * self.rules.gold_loss / 100;
+        let floor = self.curve.min_xp(self.level);
+        let xp = (self.xp * self.rules.xp_loss / 100).min(self.xp - floor);
+        self.gold -= gold;
+        self.xp -= xp;
+        if gold > 0 {
+            println!("You lost {} gold!", gold.bold());
+        }
+        if xp > 0 {
+            println!("You lost {} experience points!", xp.bold());
+        }
+    }
+    /// Remove the items which are left behind on the player's corpse.
+    pub fn drop_items(&mut self) -> Inventory {
+        self.inventory.split_off_pct(self.rules.item_drop)
     }
     pub fn receive_damage(&mut self, amount: i64) {
         self.hp.receive_damage(amount);
     }
     pub fn armor_reduction(&self, damage: i64) -> i64 {
         const U: i64 = 10; // maximum armor
-        let a = self.armor();
+        let a = self.armor().min(U - 1);
         (U - a) * damage / U
     }
-    pub fn receive_melee_attack(&mut self, monster: &mut Monster) {
+    /// Returns `true` if the attack would stun the player, unless they are
+    /// immune.
+    pub fn receive_melee_attack(&mut self, monster: &mut Monster) -> bool {
         match monster.kind {
             MonsterKind::Fairy => {
                 println!(
@@ -123,9 +288,21 @@ impl Player {
                     Health::HP
                 );
                 self.receive_damage(monster.strength);
+                false
             }
             kind => {
                 let attack = monster.produce_melee_attack();
+                if rand::thread_rng().gen_range(0..100) < self.evasion() {
+                    println!("You evade the {kind}'s {} attack!", attack.kind);
+                    return false;
+                }
+                if rand::thread_rng().gen_range(0..100) < self.equipment.block_chance() {
+                    println!("You block the {kind}'s {} attack!", attack.kind);
+                    if let Some(kind) = self.equipment.wear_shield() {
+                        println!("Your {kind} broke!");
+                    }
+                    return false;
+                }
                 let amount = self.armor_reduction(attack.damage);
                 let melee = attack.kind;
                 println!(
@@ -133,6 +310,16 @@ impl Player {
                     amount.magenta()
                 );
                 self.receive_damage(amount);
+                self.wear_armor(amount);
+                if kind.is_venomous()
+                    && !self.poisoned
+                    && !self.equipment.is_poison_immune()
+                    && rand::thread_rng().gen_bool(0.3)
+                {
+                    println!("You are poisoned!");
+                    self.poisoned = true;
+                }
+                attack.stuns()
             }
         }
     }
@@ -171,14 +358,33 @@ impl Player {
     pub fn cast_melee(&mut self, melee: MeleeAttack) -> Option<MeleeAttack> {
         self.tp.cast_melee(melee)
     }
+    /// Pay the cost of the ability, returning `false` if the player cannot afford it.
+    pub fn use_ability(&mut self, ability: Ability) -> bool {
+        if self.tp.current >= ability.tp_cost() && self.mp.current >= ability.mp_cost() {
+            self.tp.spend(ability.tp_cost());
+            self.mp.spend(ability.mp_cost());
+            true
+        } else {
+            false
+        }
+    }
 
-    pub fn consume(&mut self, item: Consumable) {
+    pub fn consume(&mut self, item: Consumable) -> ItemEffect {
         let prev_hp = self.hp.current;
         let prev_mp = self.mp.current;
-        self.hp.restore(item.healing());
-        self.mp.restore(item.mana_restore());
+        let prev_tp = self.tp.current;
+        let bonus = 100 + self.skills.consumable_bonus();
+        self.hp.restore(item.healing() * bonus / 100);
+        self.mp.restore(item.mana_restore() * bonus / 100);
+        self.tp.restore(item.tp_restore() * bonus / 100);
+        if item == Elixir {
+            self.hp.restore_all();
+            self.mp.restore_all();
+            self.tp.restore(self.tp.max);
+        }
         let heal_amt = Painted::new(self.hp.current - prev_hp).magenta();
         let mana_amt = Painted::new(self.mp.current - prev_mp).magenta();
+        let tech_amt = Painted::new(self.tp.current - prev_tp).magenta();
         match item {
             Food => println!(
                 "Your {item} heals you for {heal_amt} {} and restores {mana_amt} of your {}!",
@@ -187,35 +393,177 @@ impl Player {
             ),
             HealthPotion => println!("Your {item} heals you for {heal_amt} {}!", Health::HP),
             ManaPotion => println!("Your {item} heals you for {mana_amt} {}!", Mana::MP),
-        }
-    }
-    pub fn visit_inventory(&mut self) -> bool {
-        match self.inventory.menu(&self.inventory_message()) {
-            InventoryTransaction::Use(item) => {
-                if let Some(item) = self.inventory.pop_item(item) {
-                    self.consume(item);
+            TpTonic => println!("Your {item} restores {tech_amt} {}!", Technical::TP),
+            Elixir => println!(
+                "Your {item} restores {heal_amt} {}, {mana_amt} {} and {tech_amt} {}!",
+                Health::HP,
+                Mana::MP,
+                Technical::TP
+            ),
+            Antidote => {
+                if self.poisoned {
+                    println!("Your {item} cures the poison!");
+                } else {
+                    println!("Your {item} has no effect.");
                 }
-                true
+                self.poisoned = false;
             }
-            InventoryTransaction::Drop(item) => {
-                self.inventory.drop_item(item);
-                true
+            SmokeBomb => println!("Your {item} fills the air with smoke!"),
+            Bomb => println!("You throw the {item}!"),
+            ReturnScroll => println!("Your {item} glows brightly..."),
+        }
+        item.effect()
+    }
+    /// Returns `None` if the player left the menu. An item which cannot be
+    /// used here keeps the player in the menu.
+    pub fn visit_inventory(&mut self, setting: Setting) -> Option<ItemEffect> {
+        loop {
+            match self.inventory.menu(&self.inventory_message()) {
+                InventoryTransaction::Use(item) => {
+                    if !item.usability().allows(setting) {
+                        println!("The {item} can only be used {}!", item.usability());
+                    } else if let Some(item) = self.inventory.pop_item(item) {
+                        return Some(self.consume(item));
+                    }
+                }
+                InventoryTransaction::Drop(item) => {
+                    self.inventory.drop_item(item);
+                    return Some(ItemEffect::Nothing);
+                }
+                InventoryTransaction::Quit => return None,
             }
-            InventoryTransaction::Quit => false,
         }
     }
-    pub fn equip(&mut self, item: Gear) -> Gear {
-        self.equipment.equip(item)
+    /// Returns the displaced pieces, which may fill both hands.
+    pub fn equip(&mut self, item: Piece) -> Vec<Piece> {
+        let old = self.equipment.equip(item);
+        self.update_max_hp();
+        old
+    }
+    /// Returns the displaced pieces, or `item` itself if it does not fit
+    /// the off-hand.
+    pub fn equip_offhand(&mut self, item: Piece) -> Vec<Piece> {
+        let old = self.equipment.equip_offhand(item);
+        self.update_max_hp();
+        old
+    }
+    pub fn unequip(&mut self, item: Gear) -> Piece {
+        let old = self.equipment.unequip(item);
+        self.update_max_hp();
+        old
+    }
+    /// The change in stats from equipping `piece`, in the off-hand if
+    /// `offhand`, for confirmation.
+    pub fn comparison_message(&self, piece: &Piece, offhand: bool) -> String {
+        let (old, delta) = self.equipment.compare(piece, offhand);
+        let mut s = String::with_capacity(1 << 9);
+        if old.is_empty() {
+            write!(s, "Equip {}? ({})", piece, delta).unwrap();
+        } else {
+            let names: Vec<_> = old.iter().map(|x| x.to_string()).collect();
+            write!(
+                s,
+                "Equip {} in place of {}? ({})",
+                piece,
+                names.join(" and "),
+                delta
+            )
+            .unwrap();
+        }
+        for x in old.iter().chain([piece]) {
+            write!(s, "\n    {:<40} | {}", format!("{}", x), x.description()).unwrap();
+        }
+        s
     }
-    pub fn unequip(&mut self, item: Gear) -> Gear {
-        self.equipment.unequip(item)
+    pub fn wear_weapon(&mut self) {
+        if let Some(kind) = self.equipment.wear_weapon() {
+            println!("Your {kind} broke!");
+            self.update_max_hp();
+        }
+    }
+    pub fn wear_armor(&mut self, damage: i64) {
+        let broken = self.equipment.wear_armor(damage.max(0) as usize);
+        for kind in broken.iter() {
+            println!("Your {kind} broke!");
+        }
+        if !broken.is_empty() {
+            self.update_max_hp();
+        }
+    }
+    /// Gold needed to repair the player's gear, optionally only one kind.
+    pub fn repair_cost(&self, kind: Option<Gear>) -> usize {
+        self.equipment
+            .damaged(kind)
+            .map(Piece::repair_cost)
+            .chain(
+                self.equipment_bag
+                    .damaged(kind)
+                    .map(|(piece, count)| piece.repair_cost() * count),
+            )
+            .sum()
+    }
+    pub fn repair(&mut self, kind: Option<Gear>) {
+        self.equipment.repair(kind);
+        self.equipment_bag.repair(kind);
+        self.update_max_hp();
+    }
+    pub fn repair_message(&self) -> String {
+        let mut s = String::with_capacity(1 << 10);
+        writeln!(
+            s,
+            "                                   | {} |  {}",
+            "durability".underline(),
+            "price".underline(),
+        )
+        .unwrap();
+        let equipped = self.equipment.damaged(None).map(|x| (x, 1, "(equipped)"));
+        let bagged = self.equipment_bag.damaged(None).map(|(x, n)| (x, n, ""));
+        for (piece, count, note) in equipped.chain(bagged) {
+            writeln!(
+                s,
+                "    {:<40} {:<10} | {:^10} | {:>3} {}",
+                format!("{}", piece),
+                note,
+                piece.durability_str(),
+                piece.repair_cost() * count,
+                "gold".bold().yellow(),
+            )
+            .unwrap();
+        }
+        s
+    }
+    /// Changing gear never kills the player.
+    fn update_max_hp(&mut self) {
+        self.hp.set_max(self.max_hp());
+        self.hp.current = self.hp.current.max(1);
     }
     pub fn visit_equipment(&mut self) -> bool {
         match self.equipment_bag.menu(&self.equipment_message()) {
             EquipmentTransaction::Equip(item) => {
-                if let Some(item) = self.equipment_bag.pop_item(item) {
-                    let item = self.equip(item);
-                    self.equipment_bag.push(item);
+                if let Some(piece) = self.equipment_bag.get(item) {
+                    if confirm(&self.comparison_message(piece, false)) {
+                        if let Some(item) = self.equipment_bag.pop_item(item) {
+                            for old in self.equip(item) {
+                                self.equipment_bag.push(old);
+                            }
+                        }
+                    }
+                }
+                true
+            }
+            EquipmentTransaction::Offhand(item) => {
+                match self.equipment_bag.get(item) {
+                    Some(piece) if !piece.kind.is_shield() && !piece.is_one_handed() => {
+                        println!("Only a shield or a one-handed weapon fits the off-hand!");
+                    }
+                    Some(piece) if confirm(&self.comparison_message(piece, true)) => {
+                        if let Some(item) = self.equipment_bag.pop_item(item) {
+                            for old in self.equip_offhand(item) {
+                                self.equipment_bag.push(old);
+                            }
+                        }
+                    }
+                    _ => (),
                 }
                 true
             }
@@ -227,17 +575,164 @@ impl Player {
             EquipmentTransaction::Quit => false,
         }
     }
-    pub fn noncombat_inventory(&mut self) {
-        while self.visit_inventory() {}
+    /// Returns `true` if the player used an item which warps them to town.
+    pub fn noncombat_inventory(&mut self, setting: Setting) -> bool {
+        while let Some(effect) = self.visit_inventory(setting) {
+            if effect == ItemEffect::Warp {
+                return true;
+            }
+        }
+        false
+    }
+    /// Returns `false` if the player does not own the accessory.
+    pub fn wear_accessory(&mut self, accessory: Accessory) -> bool {
+        if self.accessories.pop(accessory).is_none() {
+            return false;
+        }
+        if let Some(old) = self.equipment.equip_accessory(accessory) {
+            self.accessories.push(old);
+        }
+        true
+    }
+    pub fn remove_accessory(&mut self, slot: AccessorySlot) {
+        if let Some(old) = self.equipment.unequip_accessory(slot) {
+            self.accessories.push(old);
+        }
     }
     pub fn noncombat_equipment(&mut self) {
         while self.visit_equipment() {}
     }
+    pub fn upgrade(&mut self, kind: Gear) {
+        let Some(piece) = self.equipment.get_mut(kind) else {
+            println!("You have no {kind} equipped!");
+            return;
+        };
+        if !piece.can_upgrade() {
+            println!("Your {piece} cannot be upgraded any further!");
+            return;
+        }
+        let gold = piece.upgrade_gold();
+        let materials = piece.upgrade_materials();
+        if !self.materials.contains_all(&materials) {
+            println!("Insufficient materials!");
+        } else if self.gold < gold {
+            println!("Insufficient gold!");
+        } else {
+            self.materials.remove_all(&materials);
+            self.gold -= gold;
+            if rand::thread_rng().gen_range(0..100) < piece.upgrade_chance() {
+                piece.upgrade += 1;
+                println!("Your {piece} was upgraded!");
+            } else {
+                println!("The upgrade of your {piece} failed!");
+            }
+        }
+    }
+    pub fn enchant(&mut self, element: Offense) {
+        if !self.equipment.weapon().is_weapon() {
+            println!("You have no weapon equipped!");
+            return;
+        }
+        let materials = enchant_materials(element);
+        if !self.materials.contains_all(&materials) {
+            println!("Insufficient materials!");
+        } else if self.gold < ENCHANT_GOLD {
+            println!("Insufficient gold!");
+        } else {
+            self.materials.remove_all(&materials);
+            self.gold -= ENCHANT_GOLD;
+            let kind = self.equipment.weapon().kind;
+            if let Some(weapon) = self.equipment.get_mut(kind) {
+                weapon.enchantment = Some(element);
+                println!("Your {weapon} is now enchanted with {element}!");
+            }
+        }
+    }
+    /// Returns `true` if the recipe was crafted.
+    pub fn craft(&mut self, recipe: Recipe) -> bool {
+        let ingredients = recipe.ingredients();
+        if !self.materials.contains_all(&ingredients) {
+            println!("Insufficient materials!");
+            false
+        } else if self.gold < recipe.gold() {
+            println!("Insufficient gold!");
+            false
+        } else {
+            self.materials.remove_all(&ingredients);
+            self.gold -= recipe.gold();
+            match recipe.product() {
+                Product::Consumable(item, count) => self.inventory.push_multiple(item, count),
+                Product::Gear(piece) => self.equipment_bag.push(piece),
+            }
+            true
+        }
+    }
+    /// Weight of the consumables and unworn gear being carried.
+    pub fn load(&self) -> usize {
+        self.inventory.weight() + self.equipment_bag.weight()
+    }
+    pub fn capacity(&self) -> usize {
+        (BASE_CAPACITY + CAPACITY_PER_STRENGTH * self.strength()).max(0) as usize
+    }
+    /// Overloaded players are slowed in the open world.
+    pub fn is_overloaded(&self) -> bool {
+        self.load() > self.capacity()
+    }
+    fn load_message(&self) -> String {
+        let mut s = format!(
+            "{}: {}/{}",
+            "Load".bold().underline(),
+            self.load(),
+            self.capacity()
+        );
+        if self.is_overloaded() {
+            s.push_str(&format!(" ({})", "overloaded".red()));
+        }
+        s
+    }
+    /// Take the loot, asking the player to make room if it is too heavy.
+    /// Returns the loot left behind, if any.
+    pub fn pick_up(&mut self, loot: Loot) -> Option<Loot> {
+        while loot.weight() > 0 && self.load() + loot.weight() > self.capacity() {
+            let msg = format!(
+                "{} (loot weighs {})\n{}{}",
+                self.load_message(),
+                loot.weight(),
+                self.inventory,
+                self.equipment_bag
+            );
+            match pickup_menu(&msg) {
+                PickupTransaction::Drop { item, count } => match item {
+                    Item::Consumable(x) => self.inventory.drop_multiple(x, count),
+                    Item::Gear(x) => self.equipment_bag.drop_multiple(x, count),
+                },
+                PickupTransaction::Take => break,
+                PickupTransaction::Leave => {
+                    let (loot, rest) = loot.split_weightless();
+                    if loot.is_empty() {
+                        println!("You leave the loot behind.");
+                    } else {
+                        println!("You take what weighs nothing, and leave the rest behind.");
+                        self.acquire(loot);
+                    }
+                    return Some(rest);
+                }
+            }
+        }
+        self.acquire(loot);
+        None
+    }
     pub fn acquire(&mut self, loot: Loot) {
         self.inventory.push_multiple(loot.item, loot.amount);
+        if let Some((material, count)) = loot.material {
+            self.materials.push_multiple(material, count);
+        }
         if let Some(gear) = loot.gear {
             self.equipment_bag.push(gear);
         }
+        if let Some(accessory) = loot.accessory {
+            self.accessories.push(accessory);
+        }
     }
     pub fn status(&self) -> String {
         let mut buf = String::with_capacity(1 << 7);
@@ -245,89 +740,229 @@ impl Player {
         buf
     }
     pub fn write_status<T: fmt::Write>(&self, buf: &mut T) -> fmt::Result {
-        write!(buf, "{} {} {}", self.hp, self.mp, self.tp)
+        write!(buf, "{} {} {}", self.hp, self.mp, self.tp)?;
+        if self.poisoned {
+            write!(buf, " {}", "PSN".bold().magenta())?;
+        }
+        Ok(())
     }
-    pub fn sleep(&mut self) {
-        self.revive();
-        println!("You feel well-rested!");
+    /// A night at the inn; the quality of the room decides how much is
+    /// restored, and whether the player wakes well rested.
+    pub fn sleep(&mut self, room: Room) {
+        self.restore_hp(self.hp.pct_max(room.restore_pct()));
+        self.restore_mp(self.mp.max * room.restore_pct() / 100);
+        self.tp.drain_all();
+        self.poisoned = false;
+        self.rested_xp_pct = room.rested_xp_pct();
+        if self.rested_xp_pct > 0 {
+            println!(
+                "You feel well rested! (+{}% experience until you next sleep)",
+                self.rested_xp_pct
+            );
+        } else {
+            println!("You wake up in the {room}.");
+        }
     }
     pub fn inventory_message(&self) -> String {
         let mut s = String::with_capacity(1 << 10);
         writeln!(s, "{}: {}", "Gold".bold().underline(), self.gold).unwrap();
+        writeln!(s, "{}", self.load_message()).unwrap();
         writeln!(s, "{}", self.inventory).unwrap();
+        writeln!(s, "{}", self.materials).unwrap();
         s
     }
     pub fn equipment_message(&self) -> String {
         let mut s = String::with_capacity(1 << 10);
         writeln!(s, "{}", self.equipment,).unwrap();
+        writeln!(s, "{}", self.load_message()).unwrap();
         writeln!(s, "{}", self.equipment_bag).unwrap();
+        if !self.accessories.is_empty() {
+            writeln!(s, "{}", self.accessories_message()).unwrap();
+        }
         s
     }
-    pub fn trade_msg(&self) -> String {
-        let mut s = String::with_capacity(1 << 10);
-        writeln!(s, "{}", self.inventory).unwrap();
-        writeln!(s, "{}", self.equipment_bag).unwrap();
+    /// Accessories which are owned but not worn.
+    pub fn accessories_message(&self) -> String {
+        let mut s = String::with_capacity(1 << 8);
+        if self.accessories.is_empty() {
+            writeln!(s, "You have no spare accessories!").unwrap();
+        } else {
+            writeln!(s, "{}:", "Accessories".bold().underline()).unwrap();
+            for (x, count) in self.accessories.bag.iter().filter(|(_, count)| **count > 0) {
+                writeln!(
+                    s,
+                    "    {:<30} | {:^9} | {}",
+                    format!("{}", x),
+                    count,
+                    x.description()
+                )
+                .unwrap();
+            }
+        }
         s
     }
     pub fn attribute_message(&self) -> String {
         let mut s = String::with_capacity(1 << 10);
-        writeln!(s, "{}: {}", LEVEL, self.level).unwrap();
         writeln!(
             s,
-            "{}: {:<10}    ({} until next level)",
-            "Experience".bold().underline(),
-            self.xp,
-            xp_to_next_level(self.xp)
+            "{}: {:<10}    {}: {}",
+            LEVEL,
+            format!("{}/{}", self.level, self.curve.cap()),
+            "Class".bold().underline(),
+            self.class
         )
         .unwrap();
+        if self.level < self.curve.cap() {
+            writeln!(
+                s,
+                "{}: {:<10}    ({} until next level)",
+                "Experience".bold().underline(),
+                self.xp,
+                self.xp_to_next_level()
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                s,
+                "{}: {:<10}    (maximum level)",
+                "Experience".bold().underline(),
+                self.xp,
+            )
+            .unwrap();
+        }
         writeln!(s, "💰: {}", self.gold).unwrap();
+        writeln!(
+            s,
+            "{}: {} ({}% discount)",
+            "Reputation".bold().underline(),
+            self.reputation,
+            Pricing::discount(self.reputation)
+        )
+        .unwrap();
+        if self.rested_xp_pct > 0 {
+            writeln!(
+                s,
+                "{}: +{}% experience",
+                "Well rested".bold().underline(),
+                self.rested_xp_pct
+            )
+            .unwrap();
+        }
         self.write_status(&mut s).unwrap();
         s.push('\n');
-        writeln!(s, "{}: {}", "STR".bold().underline(), self.strength()).unwrap();
-        writeln!(s, "{}: {}", "INT".bold().underline(), self.intellect()).unwrap();
+        writeln!(s, "{}: {}", Strength, self.strength()).unwrap();
+        writeln!(s, "{}: {}", Intellect, self.intellect()).unwrap();
+        for attr in [Vitality, Wisdom, Agility, Luck] {
+            writeln!(s, "{}: {}", attr, self.attributes.get(attr)).unwrap();
+        }
         writeln!(s, "{}: {}", "ARMOR".bold().underline(), self.armor()).unwrap();
+        for slot in [AccessorySlot::Ring, AccessorySlot::Amulet] {
+            if let Some(x) = self.equipment.accessory(slot) {
+                write!(s, "{}: {x} ({})", slot.bold().underline(), x.description()).unwrap();
+                if x == PhoenixAmulet && self.phoenix_spent {
+                    write!(s, " [spent until tomorrow]").unwrap();
+                }
+                s.push('\n');
+            }
+        }
+        if self.attribute_points > 0 {
+            writeln!(
+                s,
+                "({} unspent attribute points)",
+                self.attribute_points.bold()
+            )
+            .unwrap();
+        }
+        if self.skill_points > 0 {
+            writeln!(s, "({} unspent skill points)", self.skill_points.bold()).unwrap();
+        }
         s
     }
 
-    pub fn can_perform(&self, transaction: &Transaction) -> bool {
+    pub fn visit_skills(&mut self) -> bool {
+        match skill_menu(&self.skills, self.skill_points) {
+            SkillTransaction::Unlock(perk) => {
+                if self.skill_points < perk.cost() {
+                    println!("Insufficient skill points!");
+                } else if self.skills.unlock(perk) {
+                    self.skill_points -= perk.cost();
+                    println!("You unlocked {perk}!");
+                } else {
+                    println!("You cannot unlock {perk}!");
+                }
+                true
+            }
+            SkillTransaction::Quit => false,
+        }
+    }
+    pub fn noncombat_skills(&mut self) {
+        while self.visit_skills() {}
+    }
+    pub fn view_stats(&mut self) {
+        println!("{}", self.attribute_message());
+        self.allocate_attributes();
+    }
+
+    /// Whether the player can afford, or has the items for, a trade worth `price`.
+    pub fn can_perform(&self, transaction: &Transaction, price: usize) -> bool {
         match transaction {
-            Transaction::Buy { .. } => self.gold >= transaction.total_cost(),
+            Transaction::Buy { .. } => self.gold >= price,
             Transaction::Sell { item, count } => match item {
                 Item::Consumable(x) => self.inventory.n_available(x) >= *count,
                 Item::Gear(x) => self.equipment_bag.n_available(x) >= *count,
             },
-            Transaction::Quit => true,
+            _ => true,
+        }
+    }
+    pub(crate) fn n_available(&self, item: &Item) -> usize {
+        match item {
+            Item::Consumable(x) => self.inventory.n_available(x),
+            Item::Gear(x) => self.equipment_bag.n_available(x),
+        }
+    }
+    /// Whether the player holds `count` of `item`; for gear, exactly the given pieces.
+    pub(crate) fn has_goods(&self, item: Item, count: usize, gear: &[Piece]) -> bool {
+        match item {
+            Item::Consumable(x) => self.inventory.n_available(&x) >= count,
+            Item::Gear(_) => {
+                let mut bag = self.equipment_bag.clone();
+                bag.take(gear)
+            }
         }
     }
-    pub fn perform(&mut self, transaction: &Transaction) {
+    pub(crate) fn remove_goods(&mut self, item: Item, count: usize, gear: &[Piece]) {
+        match item {
+            Item::Consumable(x) => self.inventory.drop_multiple(x, count),
+            Item::Gear(_) => {
+                self.equipment_bag.take(gear);
+            }
+        }
+    }
+    pub(crate) fn add_goods(&mut self, item: Item, count: usize, gear: &[Piece]) {
+        match item {
+            Item::Consumable(x) => self.inventory.push_multiple(x, count),
+            Item::Gear(_) => gear.iter().for_each(|x| self.equipment_bag.push(x.clone())),
+        }
+    }
+    /// `gear` holds the pieces handed over by the merchant, if buying gear.
+    pub fn perform(&mut self, transaction: &Transaction, price: usize, gear: Vec<Piece>) {
+        self.reputation += reputation_gain(price);
         match transaction {
             Transaction::Buy { item, count } => {
-                let cost = transaction.total_cost();
-                self.gold -= cost;
+                self.gold -= price;
                 match item {
                     Item::Consumable(x) => self.inventory.push_multiple(*x, *count),
-                    Item::Gear(x) => self.equipment_bag.push_multiple(*x, *count),
-                }
-                match *count {
-                    0 => (),
-                    1 => println!("You bought 1 {} for {} gold.", item, cost),
-                    n => println!("You bought {n} {}s for {} gold.", item, cost),
+                    Item::Gear(_) => gear.into_iter().for_each(|x| self.equipment_bag.push(x)),
                 }
             }
             Transaction::Sell { item, count } => {
-                let cost = transaction.total_cost();
-                self.gold += cost;
                 match item {
                     Item::Consumable(x) => self.inventory.drop_multiple(*x, *count),
                     Item::Gear(x) => self.equipment_bag.drop_multiple(*x, *count),
                 }
-                match *count {
-                    0 => (),
-                    1 => println!("You sold 1 {} for {} gold.", item, cost),
-                    n => println!("You sold {n} {}s for {} gold.", item, cost),
-                }
+                self.gold += price;
             }
-            Transaction::Quit => (),
+            _ => (),
         }
     }
     pub fn describe_rejected_transaction(&self, transaction: &Transaction) {
@@ -345,7 +980,7 @@ impl Player {
                     }
                 }
             },
-            Transaction::Quit => (),
+            _ => (),
         }
     }
 }
@@ -0,0 +1,494 @@
+use crate::{consumable::*, monster::*, player::Player, trade::Pricing, utils::is_quit};
+use rand::Rng;
+use regex::Regex;
+use std::{
+    fmt::{self, Write as _},
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+/// Quests posted on the board each day.
+pub const QUESTS_ON_BOARD: usize = 3;
+/// Quests which the player may pursue at once.
+pub const MAX_ACTIVE_QUESTS: usize = 3;
+/// Gold paid for fetched goods, in percent of the least they could cost.
+pub const FETCH_REWARD_PCT: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    Kill {
+        kind: MonsterKind,
+        count: usize,
+    },
+    Fetch {
+        item: Consumable,
+        count: usize,
+    },
+    /// Reach the maze at the given index of the `MazeGraph`
+    Explore {
+        node: usize,
+    },
+    ClearDungeon,
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Kill { kind, count: 1 } => write!(f, "Slay 1 {}", kind.singular_painted()),
+            Self::Kill { kind, count } => write!(f, "Slay {count} {}", kind.plural_painted()),
+            Self::Fetch { item, count } => write!(f, "Bring back {count} {item}"),
+            Self::Explore { node } => write!(f, "Reach area {node} of the open world"),
+            Self::ClearDungeon => write!(f, "Clear a dungeon"),
+        }
+    }
+}
+
+/// e.g. `kill 3 wolves`, `fetch 2 health potion`, `explore 1`, `dungeon`
+impl FromStr for Objective {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_KILL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^(?:kill|slay)\s+(\d+)\s+(.+)$").unwrap());
+        static RE_FETCH: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^fetch\s+(\d+)\s+(.+)$").unwrap());
+        static RE_EXPLORE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^explore\s+(\d+)$").unwrap());
+        static RE_DUNGEON: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:clear )?dungeon$").unwrap());
+
+        let s = s.trim();
+        if let Some(caps) = RE_KILL.captures(s) {
+            if let (Ok(count), Ok(kind)) = (caps[1].parse(), caps[2].parse::<MonsterKind>()) {
+                return Ok(Self::Kill { kind, count });
+            }
+        } else if let Some(caps) = RE_FETCH.captures(s) {
+            if let (Ok(count), Ok(item)) = (caps[1].parse(), caps[2].parse::<Consumable>()) {
+                return Ok(Self::Fetch { item, count });
+            }
+        } else if let Some(caps) = RE_EXPLORE.captures(s) {
+            if let Ok(node) = caps[1].parse() {
+                return Ok(Self::Explore { node });
+            }
+        } else if RE_DUNGEON.is_match(s) {
+            return Ok(Self::ClearDungeon);
+        }
+        Err(s.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reward {
+    pub(crate) gold: usize,
+    pub(crate) xp: usize,
+    pub(crate) item: Option<Consumable>,
+}
+
+impl Reward {
+    /// A reward fitting the effort of `objective` at the player's `level`.
+    /// Fetching pays less than the goods cost to buy, and no extra item.
+    pub fn new(objective: Objective, level: usize, item: Option<Consumable>) -> Self {
+        let (gold, xp) = match objective {
+            Objective::Kill { kind, count } => {
+                let x = kind.experience_points() * count;
+                (x, x)
+            }
+            Objective::Fetch { item, count } => {
+                let price = Pricing::DEFAULT.lowest_buy_price(item.cost()) * count;
+                return Self {
+                    gold: price * FETCH_REWARD_PCT / 100,
+                    xp: 10 * count,
+                    item: None,
+                };
+            }
+            Objective::Explore { .. } => (30, 50),
+            Objective::ClearDungeon => (60, 100),
+        };
+        Self {
+            gold: gold * (9 + level) / 10,
+            xp: xp * (9 + level) / 10,
+            item,
+        }
+    }
+}
+
+impl fmt::Display for Reward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} gold, {} XP", self.gold, self.xp)?;
+        if let Some(item) = self.item {
+            write!(f, ", {item}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quest {
+    pub(crate) objective: Objective,
+    pub(crate) reward: Reward,
+    /// Kills of the quest's monster recorded before it was accepted
+    baseline: usize,
+    /// Whether an area was reached or a dungeon cleared since accepting
+    reached: bool,
+}
+
+impl Quest {
+    pub fn new(objective: Objective, reward: Reward) -> Self {
+        Self {
+            objective,
+            reward,
+            baseline: 0,
+            reached: false,
+        }
+    }
+    /// A quest suited to the player's `level`, in a world of `n_mazes` mazes.
+    pub fn gen<T: Rng>(rng: &mut T, level: usize, n_mazes: usize) -> Self {
+        let objective = match rng.gen_range(0..4) {
+            0 => {
+                let kind = loop {
+                    let kind = MonsterKind::gen(rng);
+                    if kind != Fairy && !kind.is_boss() {
+                        break kind;
+                    }
+                };
+                Objective::Kill {
+                    kind,
+                    count: rng.gen_range(2..=4),
+                }
+            }
+            1 => Objective::Fetch {
+                item: Consumable::gen(rng),
+                count: rng.gen_range(2..=4),
+            },
+            2 if n_mazes > 1 => Objective::Explore {
+                node: rng.gen_range(1..n_mazes),
+            },
+            _ => Objective::ClearDungeon,
+        };
+        let item = rng.gen_bool(0.5).then(|| Consumable::gen(rng));
+        Self::new(objective, Reward::new(objective, level, item))
+    }
+    /// Begin tracking progress from the player's current standing.
+    pub fn accept(&mut self, player: &Player) {
+        if let Objective::Kill { kind, .. } = self.objective {
+            self.baseline = player.scoreboard.count(kind);
+        }
+        self.reached = false;
+    }
+    /// Progress towards, and the goal of, the objective.
+    pub fn progress(&self, player: &Player) -> (usize, usize) {
+        match self.objective {
+            Objective::Kill { kind, count } => {
+                let kills = player.scoreboard.count(kind) - self.baseline;
+                (kills.min(count), count)
+            }
+            Objective::Fetch { item, count } => {
+                (player.inventory.n_available(&item).min(count), count)
+            }
+            Objective::Explore { .. } | Objective::ClearDungeon => (self.reached as usize, 1),
+        }
+    }
+    pub fn is_complete(&self, player: &Player) -> bool {
+        let (current, goal) = self.progress(player);
+        current >= goal
+    }
+}
+
+impl fmt::Display for Quest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<40} | {}", self.objective.to_string(), self.reward)
+    }
+}
+
+/// The player's active quests.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Journal(pub(crate) Vec<Quest>);
+
+impl Journal {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// The player reached the maze at `node`.
+    pub fn explore(&mut self, node: usize) {
+        for quest in self.0.iter_mut() {
+            if quest.objective == (Objective::Explore { node }) {
+                quest.reached = true;
+            }
+        }
+    }
+    /// The player defeated every monster in a dungeon.
+    pub fn clear_dungeon(&mut self) {
+        for quest in self.0.iter_mut() {
+            if quest.objective == Objective::ClearDungeon {
+                quest.reached = true;
+            }
+        }
+    }
+    pub fn message(&self, player: &Player) -> String {
+        let mut s = String::with_capacity(1 << 9);
+        writeln!(s, "{}:", "Journal".bold().underline()).unwrap();
+        if self.is_empty() {
+            writeln!(s, "    No active quests").unwrap();
+        }
+        for (i, quest) in self.0.iter().enumerate() {
+            let (current, goal) = quest.progress(player);
+            let progress = format!("[{current}/{goal}]");
+            let progress = if current >= goal {
+                progress.green().bold()
+            } else {
+                progress.yellow()
+            };
+            writeln!(s, "{:>3} {} {}", i + 1, progress, quest).unwrap();
+        }
+        s
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestTransaction {
+    Accept(usize),
+    TurnIn(usize),
+    Abandon(usize),
+    Quit,
+}
+
+impl FromStr for QuestTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_ACCEPT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:accept|a)$").unwrap());
+        static RE_TURN_IN: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:turn in|complete|c)$").unwrap());
+        static RE_ABANDON: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:abandon|d)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.rsplit_once(' ') {
+            if let Ok(i) = rhs.parse::<usize>() {
+                let lhs = lhs.trim();
+                if RE_ACCEPT.is_match(lhs) {
+                    return Ok(Self::Accept(i));
+                } else if RE_TURN_IN.is_match(lhs) {
+                    return Ok(Self::TurnIn(i));
+                } else if RE_ABANDON.is_match(lhs) {
+                    return Ok(Self::Abandon(i));
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(Self::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+/// Posts quests in town, and pays out for completed ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestBoard {
+    offers: Vec<Quest>,
+}
+
+impl QuestBoard {
+    pub fn new(level: usize, n_mazes: usize) -> Self {
+        let mut board = Self { offers: Vec::new() };
+        board.refresh(level, n_mazes);
+        board
+    }
+    /// A new day: post a fresh set of quests.
+    pub fn refresh(&mut self, level: usize, n_mazes: usize) {
+        let mut rng = rand::thread_rng();
+        self.offers = (0..QUESTS_ON_BOARD)
+            .map(|_| Quest::gen(&mut rng, level, n_mazes))
+            .collect();
+    }
+    pub fn message(&self, player: &Player) -> String {
+        let mut s = String::with_capacity(1 << 10);
+        writeln!(s, "{}:", "Posted".bold().underline()).unwrap();
+        if self.offers.is_empty() {
+            writeln!(s, "    Nothing today; come back tomorrow").unwrap();
+        }
+        for (i, quest) in self.offers.iter().enumerate() {
+            writeln!(s, "{:>3} {}", i + 1, quest).unwrap();
+        }
+        write!(s, "{}", player.journal.message(player)).unwrap();
+        s
+    }
+    pub fn menu(&self, msg: &str) -> QuestTransaction {
+        let mut buf = String::with_capacity(1 << 7);
+        println!("---- Reading the quest board... ----");
+        println!("{}", msg);
+        println!("e.g. `accept 1`, `turn in 2`, `abandon 1`; `q` to leave");
+        let n = msg.lines().count() + 3;
+        loop {
+            String::clear(&mut buf);
+            print!("📜 ");
+            io::Write::flush(&mut io::stdout()).unwrap();
+
+            let stdin = io::stdin();
+            let mut handle = stdin.lock();
+            match handle.read_line(&mut buf) {
+                Ok(_) => {
+                    let _ = crate::readline::clear_last_n_lines(1);
+                }
+                Err(e) => println!("Error in quest board readline: {:#?}", e),
+            }
+            if let Ok(transaction) = buf.parse::<QuestTransaction>() {
+                let _ = crate::readline::clear_last_n_lines(n);
+                break transaction;
+            }
+        }
+    }
+    /// Returns `false` if the transaction is rejected.
+    pub fn perform(&mut self, player: &mut Player, transaction: QuestTransaction) -> bool {
+        match transaction {
+            QuestTransaction::Accept(i) => {
+                if player.journal.0.len() >= MAX_ACTIVE_QUESTS {
+                    println!("You may only pursue {MAX_ACTIVE_QUESTS} quests at once!");
+                    return false;
+                }
+                if i == 0 || i > self.offers.len() {
+                    println!("There is no quest {i} on the board!");
+                    return false;
+                }
+                let mut quest = self.offers.remove(i - 1);
+                quest.accept(player);
+                println!("You accepted the quest: {}.", quest.objective);
+                player.journal.0.push(quest);
+            }
+            QuestTransaction::TurnIn(i) => {
+                let Some(quest) = i.checked_sub(1).and_then(|i| player.journal.0.get(i)) else {
+                    println!("There is no quest {i} in your journal!");
+                    return false;
+                };
+                if !quest.is_complete(player) {
+                    println!("The quest is not yet complete!");
+                    return false;
+                }
+                let quest = player.journal.0.remove(i - 1);
+                if let Objective::Fetch { item, count } = quest.objective {
+                    player.inventory.drop_multiple(item, count);
+                }
+                let Reward { gold, xp, item } = quest.reward;
+                println!("Quest complete! You receive {}.", quest.reward);
+                player.gold += gold;
+                if let Some(item) = item {
+                    player.inventory.push(item);
+                }
+                player.gain_xp(xp);
+            }
+            QuestTransaction::Abandon(i) => {
+                if i == 0 || i > player.journal.0.len() {
+                    println!("There is no quest {i} in your journal!");
+                    return false;
+                }
+                let quest = player.journal.0.remove(i - 1);
+                println!("You abandoned the quest: {}.", quest.objective);
+            }
+            QuestTransaction::Quit => (),
+        }
+        true
+    }
+    pub fn visit(&mut self, player: &mut Player) -> bool {
+        match self.menu(&self.message(player)) {
+            QuestTransaction::Quit => false,
+            transaction => {
+                self.perform(player, transaction);
+                true
+            }
+        }
+    }
+    pub fn work(&mut self, player: &mut Player) {
+        while self.visit(player) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "accept 2".parse::<QuestTransaction>().unwrap(),
+            QuestTransaction::Accept(2)
+        );
+        assert_eq!(
+            "turn in 1".parse::<QuestTransaction>().unwrap(),
+            QuestTransaction::TurnIn(1)
+        );
+        assert_eq!(
+            "d 3".parse::<QuestTransaction>().unwrap(),
+            QuestTransaction::Abandon(3)
+        );
+        assert!("accept".parse::<QuestTransaction>().is_err());
+        assert_eq!(
+            "kill 3 wolves".parse::<Objective>().unwrap(),
+            Objective::Kill {
+                kind: Wolf,
+                count: 3
+            }
+        );
+        assert_eq!(
+            "fetch 2 elixir".parse::<Objective>().unwrap(),
+            Objective::Fetch {
+                item: Elixir,
+                count: 2
+            }
+        );
+        assert_eq!(
+            "dungeon".parse::<Objective>().unwrap(),
+            Objective::ClearDungeon
+        );
+    }
+
+    #[test]
+    fn fetch_reward() {
+        for i in 0..Consumable::total_variants() as u8 {
+            let item = Consumable::from_index(i);
+            for count in 1..=4 {
+                let objective = Objective::Fetch { item, count };
+                let reward = Reward::new(objective, 50, Some(Elixir));
+                let price = Pricing::DEFAULT.lowest_buy_price(item.cost()) * count;
+                assert!(reward.gold < price);
+                assert_eq!(reward.item, None);
+            }
+        }
+    }
+
+    #[test]
+    fn progress() {
+        let mut player = Player::new(crate::class::Class::Warrior);
+        let reward = Reward {
+            gold: 10,
+            xp: 0,
+            item: Some(Elixir),
+        };
+        player.scoreboard.record(Wolf);
+        let mut board = QuestBoard { offers: Vec::new() };
+        board.offers = vec![
+            Quest::new(
+                Objective::Kill {
+                    kind: Wolf,
+                    count: 2,
+                },
+                reward,
+            ),
+            Quest::new(Objective::Explore { node: 2 }, reward),
+        ];
+        assert!(board.perform(&mut player, QuestTransaction::Accept(1)));
+        assert!(board.perform(&mut player, QuestTransaction::Accept(1)));
+        assert!(!board.perform(&mut player, QuestTransaction::Accept(1)));
+
+        player.scoreboard.record(Wolf);
+        assert_eq!(player.journal.0[0].progress(&player), (1, 2));
+        player.journal.explore(1);
+        assert!(!player.journal.0[1].is_complete(&player));
+        player.journal.explore(2);
+        assert!(!board.perform(&mut player, QuestTransaction::TurnIn(1)));
+
+        let gold = player.gold;
+        assert!(board.perform(&mut player, QuestTransaction::TurnIn(2)));
+        assert_eq!(player.gold, gold + reward.gold);
+        assert_eq!(player.journal.0.len(), 1);
+    }
+}
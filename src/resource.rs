@@ -29,6 +29,11 @@ impl Health {
     pub fn is_alive(&self) -> bool {
         self.current > 0
     }
+    /// Change the maximum, moving the current value by the same amount.
+    pub fn set_max(&mut self, max: i64) {
+        self.current = (self.current + max - self.max).clamp(0, max);
+        self.max = max;
+    }
     pub fn pct_max(&self, pct: i64) -> i64 {
         (self.max * pct.clamp(0, 100)) / 100
     }
@@ -68,6 +73,14 @@ impl Mana {
     pub fn restore_all(&mut self) {
         self.current = self.max;
     }
+    pub fn spend(&mut self, amount: i64) {
+        self.current = (self.current - amount).clamp(0, self.max);
+    }
+    /// Change the maximum, moving the current value by the same amount.
+    pub fn set_max(&mut self, max: i64) {
+        self.current = (self.current + max - self.max).clamp(0, max);
+        self.max = max;
+    }
     pub fn cast_spell(&mut self, spell: SpellCast) -> Option<SpellCast> {
         let cost = spell.cost();
         if self.current >= cost {
@@ -119,6 +132,9 @@ impl Technical {
     pub fn restore(&mut self, amount: i64) {
         self.current = (self.current + amount).clamp(0, self.max);
     }
+    pub fn spend(&mut self, amount: i64) {
+        self.current = (self.current - amount).clamp(0, self.max);
+    }
     pub fn drain_all(&mut self) {
         self.current = 0;
     }
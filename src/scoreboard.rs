@@ -14,6 +14,9 @@ impl Scoreboard {
             (Dragon, 0),
         ]))
     }
+    pub fn count(&self, kind: MonsterKind) -> usize {
+        self.0.get(&kind).copied().unwrap_or(0)
+    }
     pub fn record(&mut self, kind: MonsterKind) {
         match self.0.entry(kind) {
             Entry::Occupied(mut v) => {
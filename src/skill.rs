@@ -0,0 +1,304 @@
+use crate::{melee::Melee, spell::Offense, utils::is_quit};
+use regex::Regex;
+use std::{
+    fmt,
+    io::{self, BufRead},
+    str::FromStr,
+    sync::LazyLock,
+};
+use yansi::Paint;
+
+/// Skill points granted at each level-up.
+pub(crate) const SKILL_POINTS_PER_LEVEL: usize = 1;
+
+/// A passive bonus unlocked with skill points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Perk {
+    /// Power melee costs 5 less TP
+    Discipline,
+    /// Super melee costs 10 less TP
+    Mastery,
+    /// Fire deals 10% more damage
+    Pyromancy,
+    /// Fire deals a further 25% more damage
+    Inferno,
+    /// Stone deals 10% more damage
+    Geomancy,
+    /// Consumables restore 25% more
+    Herbalism,
+    /// Selling yields 20% more gold
+    Haggling,
+}
+pub use Perk::*;
+
+impl Perk {
+    pub(crate) const fn all() -> [Perk; 7] {
+        [
+            Discipline, Mastery, Pyromancy, Inferno, Geomancy, Herbalism, Haggling,
+        ]
+    }
+    pub const fn cost(&self) -> usize {
+        match self {
+            Mastery | Inferno => 2,
+            _ => 1,
+        }
+    }
+    pub const fn prerequisites(&self) -> &'static [Perk] {
+        match self {
+            Mastery => &[Discipline],
+            Inferno => &[Pyromancy],
+            _ => &[],
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Discipline => "Power melee costs 5 less TP",
+            Mastery => "Super melee costs 10 less TP",
+            Pyromancy => "Fire deals 10% more damage",
+            Inferno => "Fire deals a further 25% more damage",
+            Geomancy => "Stone deals 10% more damage",
+            Herbalism => "Consumables restore 25% more",
+            Haggling => "Selling yields 20% more gold",
+        }
+    }
+}
+
+impl fmt::Display for Perk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        macro_rules! arm {
+            ($word:literal) => {
+                write!(f, "{}", $word.rgb(0x3c, 0xb3, 0x71))
+            };
+        }
+        match self {
+            Discipline => arm!("Discipline"),
+            Mastery => arm!("Mastery"),
+            Pyromancy => arm!("Pyromancy"),
+            Inferno => arm!("Inferno"),
+            Geomancy => arm!("Geomancy"),
+            Herbalism => arm!("Herbalism"),
+            Haggling => arm!("Haggling"),
+        }
+    }
+}
+
+impl FromStr for Perk {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        macro_rules! static_regex {
+            { $var:ident, $regex:literal } => {
+                static $var: LazyLock<Regex> = LazyLock::new(|| Regex::new($regex).unwrap());
+            }
+        }
+        static_regex! { RE_DISCIPLINE, "(?i)^discipline$" }
+        static_regex! { RE_MASTERY, "(?i)^mastery$" }
+        static_regex! { RE_PYROMANCY, "(?i)^pyromancy$" }
+        static_regex! { RE_INFERNO, "(?i)^inferno$" }
+        static_regex! { RE_GEOMANCY, "(?i)^geomancy$" }
+        static_regex! { RE_HERBALISM, "(?i)^herbalism$" }
+        static_regex! { RE_HAGGLING, "(?i)^haggling$" }
+
+        if RE_DISCIPLINE.is_match(s) {
+            Ok(Discipline)
+        } else if RE_MASTERY.is_match(s) {
+            Ok(Mastery)
+        } else if RE_PYROMANCY.is_match(s) {
+            Ok(Pyromancy)
+        } else if RE_INFERNO.is_match(s) {
+            Ok(Inferno)
+        } else if RE_GEOMANCY.is_match(s) {
+            Ok(Geomancy)
+        } else if RE_HERBALISM.is_match(s) {
+            Ok(Herbalism)
+        } else if RE_HAGGLING.is_match(s) {
+            Ok(Haggling)
+        } else {
+            Err(s.to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SkillTree {
+    unlocked: Vec<Perk>,
+}
+
+impl SkillTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn has(&self, perk: Perk) -> bool {
+        self.unlocked.contains(&perk)
+    }
+    pub fn can_unlock(&self, perk: Perk) -> bool {
+        !self.has(perk) && perk.prerequisites().iter().all(|x| self.has(*x))
+    }
+    pub fn unlock(&mut self, perk: Perk) -> bool {
+        if self.can_unlock(perk) {
+            self.unlocked.push(perk);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reduction in the TP cost of a melee attack.
+    pub fn melee_discount(&self, kind: Melee) -> i64 {
+        match kind {
+            Melee::Power if self.has(Discipline) => 5,
+            Melee::Super if self.has(Mastery) => 10,
+            _ => 0,
+        }
+    }
+    /// Percentage of bonus damage dealt by an offensive spell.
+    pub fn spell_bonus(&self, kind: Offense) -> i64 {
+        match kind {
+            Offense::Fire => {
+                let mut pct = 0;
+                if self.has(Pyromancy) {
+                    pct += 10;
+                }
+                if self.has(Inferno) {
+                    pct += 25;
+                }
+                pct
+            }
+            Offense::Stone if self.has(Geomancy) => 10,
+            _ => 0,
+        }
+    }
+    /// Percentage of bonus HP and MP restored by consumables.
+    pub fn consumable_bonus(&self) -> i64 {
+        if self.has(Herbalism) {
+            25
+        } else {
+            0
+        }
+    }
+    /// Gold received when selling items worth `value`.
+    pub fn sale_price(&self, value: usize) -> usize {
+        if self.has(Haggling) {
+            value + value / 5
+        } else {
+            value
+        }
+    }
+
+    pub(crate) fn write_tree<T: fmt::Write>(&self, f: &mut T) -> fmt::Result {
+        writeln!(
+            f,
+            "                              | {} | {}",
+            "cost".underline(),
+            "effect".underline(),
+        )?;
+        for perk in Perk::all() {
+            let status = if self.has(perk) {
+                "✅"
+            } else if self.can_unlock(perk) {
+                "🔓"
+            } else {
+                "🔒"
+            };
+            write!(
+                f,
+                "{} {:<40} | {:^4} | {}",
+                status,
+                format!("{}", perk),
+                perk.cost(),
+                perk.description(),
+            )?;
+            if let [prereq, ..] = perk.prerequisites() {
+                write!(f, " (requires {})", prereq)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillTransaction {
+    Unlock(Perk),
+    Quit,
+}
+
+impl FromStr for SkillTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_UNLOCK: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:unlock|u)$").unwrap());
+
+        let s = s.trim();
+        if let Some((lhs, rhs)) = s.split_once(' ') {
+            if RE_UNLOCK.is_match(lhs) {
+                if let Ok(perk) = rhs.parse::<Perk>() {
+                    return Ok(SkillTransaction::Unlock(perk));
+                }
+            }
+        } else if is_quit(s) {
+            return Ok(SkillTransaction::Quit);
+        }
+        Err(s.to_string())
+    }
+}
+
+pub(crate) fn skill_menu(tree: &SkillTree, points: usize) -> SkillTransaction {
+    let mut buf = String::with_capacity(1 << 7);
+    let mut msg = String::with_capacity(1 << 10);
+    tree.write_tree(&mut msg).unwrap();
+    println!(
+        "---- Entering skill tree ({} skill points)... ----",
+        points.bold()
+    );
+    println!("{}", msg);
+    let n = msg.lines().count() + 2;
+    loop {
+        String::clear(&mut buf);
+        print!("🌿 ");
+        io::Write::flush(&mut io::stdout()).unwrap();
+
+        let stdin = io::stdin();
+        let mut handle = stdin.lock();
+        match handle.read_line(&mut buf) {
+            Ok(_) => {
+                let _ = crate::readline::clear_last_n_lines(1);
+            }
+            Err(e) => println!("Error in skill menu readline: {:#?}", e),
+        }
+        if let Ok(transaction) = buf.parse::<SkillTransaction>() {
+            let _ = crate::readline::clear_last_n_lines(n);
+            break transaction;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prerequisites() {
+        let mut tree = SkillTree::new();
+        assert!(!tree.unlock(Mastery));
+        assert!(tree.unlock(Discipline));
+        assert!(!tree.unlock(Discipline));
+        assert!(tree.unlock(Mastery));
+        assert_eq!(tree.melee_discount(Melee::Power), 5);
+        assert_eq!(tree.melee_discount(Melee::Super), 10);
+        assert_eq!(tree.melee_discount(Melee::Basic), 0);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "u pyromancy".parse::<SkillTransaction>().unwrap(),
+            SkillTransaction::Unlock(Pyromancy)
+        );
+        assert_eq!(
+            "unlock HAGGLING".parse::<SkillTransaction>().unwrap(),
+            SkillTransaction::Unlock(Haggling)
+        );
+        assert!("u pyro".parse::<SkillTransaction>().is_err());
+    }
+}
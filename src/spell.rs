@@ -1,4 +1,4 @@
-use crate::{resource::*, utils::is_quit};
+use crate::{class::Class, resource::*, skill::SkillTree, utils::is_quit};
 use regex::Regex;
 use std::{
     fmt,
@@ -8,7 +8,7 @@ use std::{
 };
 use yansi::Paint;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Offense {
     Stone,
     Fire,
@@ -81,6 +81,11 @@ impl OffenseSpell {
             damage: intellect * kind.damage(),
         }
     }
+    pub fn with_skills(kind: Offense, intellect: i64, skills: &SkillTree) -> Self {
+        let mut spell = Self::new(kind, intellect);
+        spell.damage += (spell.damage * skills.spell_bonus(kind)) / 100;
+        spell
+    }
     pub const fn cost(&self) -> i64 {
         self.kind.cost()
     }
@@ -235,22 +240,30 @@ impl SpellCast {
     }
 }
 
-pub(crate) fn spell_menu(intellect: i64) -> Option<SpellCast> {
-    const N: usize = 10;
+pub(crate) fn spell_menu(intellect: i64, class: Class, skills: &SkillTree) -> Option<SpellCast> {
+    let offense: Vec<_> = class
+        .offense_spells()
+        .iter()
+        .map(|kind| OffenseSpell::with_skills(*kind, intellect, skills))
+        .collect();
+    let defense: Vec<_> = class
+        .defense_spells()
+        .iter()
+        .map(|kind| DefenseSpell::new(*kind, intellect))
+        .collect();
+    let mut n: usize = 1;
     let mut buf = String::with_capacity(1 << 7);
     println!("---- Entering spell menu... ----");
-    OffenseSpell::print_menu_preface();
-    let fire = OffenseSpell::new(Offense::Fire, intellect);
-    let stone = OffenseSpell::new(Offense::Stone, intellect);
-    fire.print_menu_item();
-    stone.print_menu_item();
-    DefenseSpell::print_menu_preface();
-    let cure1 = DefenseSpell::new(Defense::Cure1, intellect);
-    let cure2 = DefenseSpell::new(Defense::Cure2, intellect);
-    let meditate = DefenseSpell::new(Defense::Meditate, intellect);
-    cure1.print_menu_item();
-    cure2.print_menu_item();
-    meditate.print_menu_item();
+    if !offense.is_empty() {
+        OffenseSpell::print_menu_preface();
+        offense.iter().for_each(OffenseSpell::print_menu_item);
+        n += 2 + offense.len();
+    }
+    if !defense.is_empty() {
+        DefenseSpell::print_menu_preface();
+        defense.iter().for_each(DefenseSpell::print_menu_item);
+        n += 2 + defense.len();
+    }
 
     loop {
         String::clear(&mut buf);
@@ -270,22 +283,17 @@ pub(crate) fn spell_menu(intellect: i64) -> Option<SpellCast> {
         let s = buf.trim();
 
         if is_quit(s) {
-            let _ = crate::readline::clear_last_n_lines(N);
+            let _ = crate::readline::clear_last_n_lines(n);
             break None;
-        } else {
-            if let Ok(offense) = s.parse::<Offense>() {
-                let _ = crate::readline::clear_last_n_lines(N);
-                return Some(SpellCast::Offense(match offense {
-                    Stone => stone,
-                    Fire => fire,
-                }));
-            } else if let Ok(defense) = s.parse::<Defense>() {
-                let _ = crate::readline::clear_last_n_lines(N);
-                return Some(SpellCast::Defense(match defense {
-                    Cure1 => cure1,
-                    Cure2 => cure2,
-                    Meditate => meditate,
-                }));
+        } else if let Ok(kind) = s.parse::<Offense>() {
+            if let Some(spell) = offense.iter().find(|spell| spell.kind == kind) {
+                let _ = crate::readline::clear_last_n_lines(n);
+                return Some(SpellCast::Offense(spell.clone()));
+            }
+        } else if let Ok(kind) = s.parse::<Defense>() {
+            if let Some(spell) = defense.iter().find(|spell| spell.kind == kind) {
+                let _ = crate::readline::clear_last_n_lines(n);
+                return Some(SpellCast::Defense(spell.clone()));
             }
         }
     }
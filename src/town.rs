@@ -1,3 +1,4 @@
+use crate::clock::Clock;
 use regex::Regex;
 use std::{
     fmt,
@@ -13,9 +14,17 @@ pub enum TownAction {
     Dungeon,
     Sleep,
     Trade,
+    Blacksmith,
+    Craft,
+    Upgrade,
+    Enchant,
+    Jeweller,
+    Bank,
+    Quests,
     Inventory,
     Equipment,
     Stats,
+    Skills,
 }
 use TownAction::*;
 
@@ -24,11 +33,19 @@ impl TownAction {
         match self {
             Adventure => "A free-form adventure",
             Dungeon => "A dungeon filled with a random number of monsters",
-            Sleep => "Restore all HP and MP; lose any stored TP",
-            Trade => "Visit the village merchant",
+            Sleep => "Rent a room at the inn until morning",
+            Trade => "Visit the shops in the market; closed at night",
+            Blacksmith => "Repair damaged gear",
+            Craft => "Craft items from monster materials",
+            Upgrade => "Upgrade equipped gear at the forge",
+            Enchant => "Enchant your weapon with an element",
+            Jeweller => "Buy and wear rings and amulets",
+            Bank => "Store gold and items safe from death",
+            Quests => "Take on quests and collect rewards",
             Inventory => "Open inventory",
             Equipment => "Open equipment",
             Stats => "Display character statistics",
+            Skills => "Browse and unlock passive skills",
         }
     }
     pub(crate) fn print_menu_item(&self) {
@@ -47,9 +64,17 @@ impl fmt::Display for TownAction {
             Dungeon => write!(f, "{}ungeon", "D".bold().underline()),
             Sleep => write!(f, "{}leep", "S".bold().underline()),
             Trade => write!(f, "{}rade", "T".bold().underline()),
+            Blacksmith => write!(f, "{}lacksmith", "B".bold().underline()),
+            Craft => write!(f, "{}raft", "C".bold().underline()),
+            Upgrade => write!(f, "{}pgrade", "U".bold().underline()),
+            Enchant => write!(f, "{}chant", "En".bold().underline()),
+            Jeweller => write!(f, "{}eweller", "J".bold().underline()),
+            Bank => write!(f, "{}nk", "Ba".bold().underline()),
+            Quests => write!(f, "{}ests", "Qu".bold().underline()),
             Inventory => write!(f, "{}nventory", "I".bold().underline()),
             Equipment => write!(f, "{}quipment", "E".bold().underline()),
             Stats => write!(f, "{}s", "Stat".bold().underline()),
+            Skills => write!(f, "{}s", "Skill".bold().underline()),
         }
     }
 }
@@ -67,17 +92,47 @@ impl FromStr for TownAction {
             LazyLock::new(|| Regex::new("(?i)^(?:sleep|s)$").unwrap());
         static RE_TRADE: LazyLock<Regex> =
             LazyLock::new(|| Regex::new("(?i)^(?:trade|t)$").unwrap());
+        static RE_SMITH: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:blacksmith|b)$").unwrap());
+        static RE_CRAFT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:craft|c)$").unwrap());
+        static RE_UPGRADE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:upgrade|u)$").unwrap());
+        static RE_ENCHANT: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:enchant|en)$").unwrap());
+        static RE_JEWEL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:jeweller|j)$").unwrap());
+        static RE_BANK: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:bank|ba)$").unwrap());
+        static RE_QUESTS: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:quests?|qu)$").unwrap());
         static RE_INV: LazyLock<Regex> =
             LazyLock::new(|| Regex::new("(?i)^(?:inventory|i)$").unwrap());
         static RE_EQUIP: LazyLock<Regex> =
             LazyLock::new(|| Regex::new("(?i)^(?:equipment|e)$").unwrap());
         static RE_STATS: LazyLock<Regex> =
             LazyLock::new(|| Regex::new("(?i)^(?:stats?)$").unwrap());
+        static RE_SKILLS: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:skills?)$").unwrap());
 
         if RE_SLEEP.is_match(s) {
             Ok(Sleep)
         } else if RE_TRADE.is_match(s) {
             Ok(Trade)
+        } else if RE_SMITH.is_match(s) {
+            Ok(Blacksmith)
+        } else if RE_CRAFT.is_match(s) {
+            Ok(Craft)
+        } else if RE_UPGRADE.is_match(s) {
+            Ok(Upgrade)
+        } else if RE_ENCHANT.is_match(s) {
+            Ok(Enchant)
+        } else if RE_JEWEL.is_match(s) {
+            Ok(Jeweller)
+        } else if RE_BANK.is_match(s) {
+            Ok(Bank)
+        } else if RE_QUESTS.is_match(s) {
+            Ok(Quests)
         } else if RE_ADV.is_match(s) {
             Ok(Adventure)
         } else if RE_DUNGEON.is_match(s) {
@@ -88,22 +143,32 @@ impl FromStr for TownAction {
             Ok(Equipment)
         } else if RE_STATS.is_match(s) {
             Ok(Stats)
+        } else if RE_SKILLS.is_match(s) {
+            Ok(Skills)
         } else {
             Err(s.to_string())
         }
     }
 }
 
-pub fn town_menu() -> TownAction {
+pub fn town_menu(clock: &Clock) -> TownAction {
     let mut buf = String::with_capacity(1 << 10);
-    println!("==== Entering the town... ====");
+    println!("==== Entering the town... ({}) ====", clock);
     Adventure.print_menu_item();
     Dungeon.print_menu_item();
     Sleep.print_menu_item();
     Trade.print_menu_item();
+    Blacksmith.print_menu_item();
+    Craft.print_menu_item();
+    Upgrade.print_menu_item();
+    Enchant.print_menu_item();
+    Jeweller.print_menu_item();
+    Bank.print_menu_item();
+    Quests.print_menu_item();
     Inventory.print_menu_item();
     Equipment.print_menu_item();
     Stats.print_menu_item();
+    Skills.print_menu_item();
     loop {
         String::clear(&mut buf);
         print!("🌆 ");
@@ -120,7 +185,7 @@ pub fn town_menu() -> TownAction {
 
         let s = buf.trim();
         if let Ok(action) = s.parse::<TownAction>() {
-            let _ = crate::readline::clear_last_n_lines(8);
+            let _ = crate::readline::clear_last_n_lines(16);
             return action;
         }
     }
@@ -1,24 +1,137 @@
 use crate::{
-    consumable::*, inventory::*, item::equipment_bag::EquipmentBag, item::*, player::Player,
+    consumable::*,
+    equipment::{Gear, Piece, Rarity},
+    inventory::*,
+    item::equipment_bag::EquipmentBag,
+    item::*,
+    player::Player,
     utils::*,
 };
+use rand::{seq::SliceRandom, Rng};
 use regex::Regex;
 use std::{
+    fmt::{self, Write as _},
     io::{self, BufRead},
     str::FromStr,
     sync::LazyLock,
 };
+use yansi::Paint;
+
+/// Reputation needed for each percent of discount.
+pub const REPUTATION_PER_DISCOUNT: usize = 10;
+/// The largest discount, in percent, which reputation can earn.
+pub const MAX_DISCOUNT: usize = 20;
+/// Gold traded for each point of reputation earned.
+pub const GOLD_PER_REPUTATION: usize = 10;
+
+/// Lowest percentage of the usual price charged for plentiful items.
+pub const MIN_DEMAND_PCT: usize = 50;
+/// Highest percentage of the usual price charged for scarce items.
+pub const MAX_DEMAND_PCT: usize = 200;
+
+/// Gold in the merchant's purse at level 1.
+pub const MERCHANT_GOLD: usize = 200;
+/// Gold added to the merchant's purse for each level of the player.
+pub const MERCHANT_GOLD_PER_LEVEL: usize = 50;
+/// How the merchant turns an item's value into a price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pricing {
+    /// Percentage added to the value of items which the merchant sells
+    pub(crate) markup: usize,
+    /// Percentage of the value paid for items which the merchant buys
+    pub(crate) buyback: usize,
+    /// Percentage by which prices move for each unit of stock below or above `par`
+    pub(crate) elasticity: usize,
+    /// Stock at which prices are neither raised nor lowered
+    pub(crate) par: usize,
+}
+
+impl Pricing {
+    /// The usual terms of trade in town.
+    pub const DEFAULT: Pricing = Pricing {
+        markup: 25,
+        buyback: 50,
+        elasticity: 5,
+        par: 5,
+    };
+    /// Scarce items cost more, and plentiful ones less, within half and
+    /// double their usual price.
+    pub fn demand_pct(&self, stock: usize) -> usize {
+        let shift = self.elasticity as i64 * (self.par as i64 - stock as i64);
+        (100 + shift).clamp(MIN_DEMAND_PCT as i64, MAX_DEMAND_PCT as i64) as usize
+    }
+    pub fn discount(reputation: usize) -> usize {
+        (reputation / REPUTATION_PER_DISCOUNT).min(MAX_DISCOUNT)
+    }
+    /// Gold charged for one item worth `value`, given the merchant's `stock`.
+    pub fn buy_price(&self, value: usize, stock: usize, reputation: usize) -> usize {
+        let price = value * (100 + self.markup) * self.demand_pct(stock) / 10_000;
+        (price * (100 - Self::discount(reputation)) / 100).max(1)
+    }
+    /// The least ever charged for one item worth `value`: plentiful, and
+    /// sold to a player with the best discount.
+    pub fn lowest_buy_price(&self, value: usize) -> usize {
+        let price = value * (100 + self.markup) * MIN_DEMAND_PCT / 10_000;
+        (price * (100 - MAX_DISCOUNT) / 100).max(1)
+    }
+    /// Gold paid for one item worth `value`, given the merchant's `stock`.
+    pub fn sell_price(&self, value: usize, stock: usize, reputation: usize) -> usize {
+        let price = value * self.buyback * self.demand_pct(stock) / 10_000;
+        price * (100 + Self::discount(reputation)) / 100
+    }
+}
+
+impl Default for Pricing {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// What a merchant deals in, and on what terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Catalogue {
+    pub(crate) name: &'static str,
+    /// Consumables kept in stock at level 1; the counts grow by a tenth
+    /// with each level of the player
+    pub(crate) consumables: &'static [(Consumable, usize)],
+    /// Kinds of gear from which the stock is drawn
+    pub(crate) gear: &'static [Gear],
+    /// Pieces of gear on offer at level 1; one more every five levels
+    pub(crate) gear_count: usize,
+    /// The least rarity of gear on offer
+    pub(crate) rarity: Rarity,
+    pub(crate) pricing: Pricing,
+    /// Percent chance of being in town on any given day
+    pub(crate) presence: u32,
+}
+
+impl Catalogue {
+    /// Merchants only buy what they sell.
+    pub fn deals_in(&self, item: &Item) -> bool {
+        match item {
+            Item::Consumable(x) => self.consumables.iter().any(|(y, _)| x == y),
+            Item::Gear(x) => self.gear.contains(x),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Merchant {
     inventory: Inventory,
-    equipment_bag: EquipmentBag,
+    pub(crate) equipment_bag: EquipmentBag,
+    catalogue: Catalogue,
+    /// Gold available to buy the player's items
+    gold: usize,
+    /// Whether the merchant is in town today
+    pub(crate) open: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TradeAction {
     Buy,
     Sell,
+    Buyback,
+    Undo,
     Quit,
 }
 
@@ -29,11 +142,18 @@ impl FromStr for TradeAction {
 
         static RE_BUY: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:buy|b)$").unwrap());
         static RE_SELL: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:sell|s)$").unwrap());
+        static RE_BUYBACK: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:buyback|bb)$").unwrap());
+        static RE_UNDO: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:undo|u)$").unwrap());
 
         if RE_BUY.is_match(s) {
             Ok(TradeAction::Buy)
         } else if RE_SELL.is_match(s) {
             Ok(TradeAction::Sell)
+        } else if RE_BUYBACK.is_match(s) {
+            Ok(TradeAction::Buyback)
+        } else if RE_UNDO.is_match(s) {
+            Ok(TradeAction::Undo)
         } else if is_quit(s) {
             Ok(TradeAction::Quit)
         } else {
@@ -44,79 +164,334 @@ impl FromStr for TradeAction {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Transaction {
-    Buy { item: Item, count: usize },
-    Sell { item: Item, count: usize },
-    Quit,
+    Buy {
+        item: Item,
+        count: usize,
+    },
+    Sell {
+        item: Item,
+        count: usize,
+    },
+    /// Re-purchase items sold during this visit, at the price they fetched
+    Buyback {
+        item: Item,
+        count: usize,
+    },
 }
 
 impl Transaction {
-    pub fn new(kind: TradeAction, item: Item, count: usize) -> Self {
+    /// Returns `None` unless `kind` is `Buy`, `Sell` or `Buyback`; the other
+    /// actions trade no goods.
+    pub fn new(kind: TradeAction, item: Item, count: usize) -> Option<Self> {
         match kind {
-            TradeAction::Quit => Transaction::Quit,
-            TradeAction::Buy => Transaction::Buy { item, count },
-            TradeAction::Sell => Transaction::Sell { item, count },
+            TradeAction::Buy => Some(Transaction::Buy { item, count }),
+            TradeAction::Sell => Some(Transaction::Sell { item, count }),
+            TradeAction::Buyback => Some(Transaction::Buyback { item, count }),
+            TradeAction::Undo | TradeAction::Quit => None,
         }
     }
-    pub fn total_cost(&self) -> usize {
-        match self {
-            Self::Quit => 0,
-            Self::Buy { item, count } => item.cost() * count,
-            Self::Sell { item, count } => item.cost() * count,
+}
+
+/// How many units of an item a command trades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Count(usize),
+    /// Everything on offer: the player's whole stack, the merchant's whole
+    /// stock, or every unit sold during this visit
+    All,
+    /// As many as the player's gold allows
+    Max,
+}
+
+impl FromStr for Quantity {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_ALL: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^all$").unwrap());
+        static RE_MAX: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^max$").unwrap());
+
+        let s = s.trim();
+        if let Ok(n) = s.parse::<usize>() {
+            Ok(Quantity::Count(n))
+        } else if RE_ALL.is_match(s) {
+            Ok(Quantity::All)
+        } else if RE_MAX.is_match(s) {
+            Ok(Quantity::Max)
+        } else {
+            Err(s.to_string())
         }
     }
 }
 
-impl FromStr for Transaction {
+/// What an order refers to: one item, or everything marked as junk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goods {
+    Item(Item),
+    Junk,
+}
+
+impl FromStr for Goods {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_JUNK: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^junk$").unwrap());
+
         let s = s.trim();
+        if RE_JUNK.is_match(s) {
+            Ok(Goods::Junk)
+        } else {
+            s.parse::<Item>().map(Goods::Item)
+        }
+    }
+}
 
-        if let Some((fst, rst)) = s.split_once(' ') {
-            if let Ok(action) = fst.parse::<TradeAction>() {
-                match action {
-                    TradeAction::Buy | TradeAction::Sell => {
-                        let rst = rst.trim();
-                        if let Ok(item) = rst.parse::<Item>() {
-                            return Ok(Transaction::new(action, item, 1));
-                        } else if let Some((lhs, rhs)) = rst.split_once(' ') {
-                            if let Ok(n) = lhs.parse::<usize>() {
-                                if let Ok(item) = rhs.parse::<Item>() {
-                                    return Ok(Transaction::new(action, item, n));
-                                }
-                            }
-                        }
-                    }
-                    TradeAction::Quit => (),
+/// A line typed at the merchant's prompt.
+///
+/// A trade may list several orders, e.g. `buy 2 hp mp max food`, which are
+/// resolved into [`Transaction`]s and applied all at once or not at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TradeCommand {
+    Trade {
+        action: TradeAction,
+        orders: Vec<(Quantity, Goods)>,
+    },
+    /// Quote the price of each item without trading
+    Price(Vec<Item>),
+    /// Mark or unmark an item as junk
+    Junk(Item),
+    Undo,
+    Quit,
+}
+
+impl TradeCommand {
+    /// Split `words` into items, taking the longest run of words which names
+    /// one, each optionally preceded by a quantity.
+    fn parse_orders(words: &[&str]) -> Option<Vec<(Quantity, Goods)>> {
+        let mut orders = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            let quantity = match words[i].parse::<Quantity>() {
+                Ok(quantity) => {
+                    i += 1;
+                    quantity
                 }
+                Err(_) => Quantity::Count(1),
+            };
+            let (j, goods) = (i + 1..=words.len())
+                .rev()
+                .find_map(|j| words[i..j].join(" ").parse::<Goods>().ok().map(|x| (j, x)))?;
+            orders.push((quantity, goods));
+            i = j;
+        }
+        (!orders.is_empty()).then_some(orders)
+    }
+    fn parse_items(words: &[&str]) -> Option<Vec<Item>> {
+        Self::parse_orders(words)?
+            .into_iter()
+            .map(|order| match order {
+                (Quantity::Count(1), Goods::Item(item)) => Some(item),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for TradeCommand {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE_PRICE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new("(?i)^(?:price|p)$").unwrap());
+        static RE_JUNK: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:junk|j)$").unwrap());
+
+        let s = s.trim();
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let Some((fst, rst)) = words.split_first() else {
+            return Err(s.to_string());
+        };
+        let command = if RE_PRICE.is_match(fst) {
+            Self::parse_items(rst).map(TradeCommand::Price)
+        } else if RE_JUNK.is_match(fst) {
+            match rst.join(" ").parse::<Item>() {
+                Ok(item) => Some(TradeCommand::Junk(item)),
+                Err(_) => None,
             }
         } else {
-            if let Ok(TradeAction::Quit) = s.parse::<TradeAction>() {
-                return Ok(Transaction::Quit);
+            match fst.parse::<TradeAction>() {
+                Ok(TradeAction::Quit) if rst.is_empty() => Some(TradeCommand::Quit),
+                Ok(TradeAction::Undo) if rst.is_empty() => Some(TradeCommand::Undo),
+                Ok(action @ (TradeAction::Buy | TradeAction::Sell | TradeAction::Buyback)) => {
+                    Self::parse_orders(rst).map(|orders| TradeCommand::Trade { action, orders })
+                }
+                _ => None,
             }
-        }
-        Err(s.to_string())
+        };
+        command.ok_or_else(|| s.to_string())
     }
 }
 
 impl Merchant {
-    pub fn new() -> Self {
-        let inventory = [(HealthPotion, 10), (ManaPotion, 10), (Food, 20)]
-            .into_iter()
-            .collect();
-        Self {
-            inventory,
-            equipment_bag: EquipmentBag::new_merchant(),
+    pub fn new(catalogue: Catalogue) -> Self {
+        let mut merchant = Self {
+            inventory: Inventory::new(),
+            equipment_bag: EquipmentBag::new(),
+            catalogue,
+            gold: 0,
+            open: false,
+        };
+        merchant.restock(1);
+        merchant
+    }
+    pub fn name(&self) -> &'static str {
+        self.catalogue.name
+    }
+    pub fn purse(level: usize) -> usize {
+        MERCHANT_GOLD + MERCHANT_GOLD_PER_LEVEL * level.saturating_sub(1)
+    }
+    /// Top up the consumables and purse, replace the gear on offer with a
+    /// fresh selection, and decide whether to open today. More of everything
+    /// is stocked as `level` rises.
+    pub(crate) fn restock_imp<T: Rng>(&mut self, rng: &mut T, level: usize) {
+        let level = level.max(1);
+        let catalogue = self.catalogue;
+        for (item, base) in catalogue.consumables.iter().copied() {
+            let target = base * (9 + level) / 10;
+            let n = self.inventory.n_available(&item);
+            self.inventory.push_multiple(item, target.saturating_sub(n));
+        }
+        let mut kinds = catalogue.gear.to_vec();
+        kinds.shuffle(rng);
+        kinds.truncate(catalogue.gear_count + level / 5);
+        self.equipment_bag = EquipmentBag::new();
+        for kind in kinds {
+            let piece = loop {
+                let piece = Piece::gen(rng, kind, level);
+                if piece.rarity >= catalogue.rarity {
+                    break piece;
+                }
+            };
+            self.equipment_bag.push(piece);
+        }
+        self.gold = self.gold.max(Self::purse(level));
+        self.open = rng.gen_range(0..100) < catalogue.presence;
+    }
+    pub fn restock(&mut self, level: usize) {
+        self.restock_imp(&mut rand::thread_rng(), level);
+    }
+    pub fn stock(&self, item: &Item) -> usize {
+        match item {
+            Item::Consumable(x) => self.inventory.n_available(x),
+            Item::Gear(x) => self.equipment_bag.n_available(x),
+        }
+    }
+    /// Gold exchanged by the transaction.
+    pub fn quote(&self, transaction: &Transaction, player: &Player) -> usize {
+        self.unit_prices(transaction, player).into_iter().sum()
+    }
+    /// Gold exchanged for each unit of the transaction. Each unit bought or
+    /// sold moves the merchant's stock, and with it the price of the next.
+    pub fn unit_prices(&self, transaction: &Transaction, player: &Player) -> Vec<usize> {
+        let pricing = self.catalogue.pricing;
+        match transaction {
+            Transaction::Buy { item, count } => {
+                let values = match item {
+                    Item::Consumable(x) => vec![x.cost(); *count],
+                    Item::Gear(x) => self
+                        .equipment_bag
+                        .cheapest(*x, *count)
+                        .iter()
+                        .map(Piece::cost)
+                        .collect(),
+                };
+                let stock = self.stock(item);
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        pricing.buy_price(value, stock.saturating_sub(i), player.reputation)
+                    })
+                    .collect()
+            }
+            Transaction::Sell { item, count } => {
+                let values = match item {
+                    Item::Consumable(x) => vec![x.cost(); *count],
+                    Item::Gear(x) => player
+                        .equipment_bag
+                        .cheapest(*x, *count)
+                        .iter()
+                        .map(Piece::cost)
+                        .collect(),
+                };
+                let stock = self.stock(item);
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        let price = pricing.sell_price(value, stock + i, player.reputation);
+                        player.skills.sale_price(price)
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
         }
     }
-    pub fn inventory_message(&self) -> String {
+    pub fn inventory_message(&self, reputation: usize) -> String {
+        let pricing = self.catalogue.pricing;
         let mut s = String::with_capacity(1 << 10);
-        self.inventory.fmt_imp(&mut s, "price").unwrap();
+        writeln!(s, "{}: {} gold", "Purse".bold().underline(), self.gold).unwrap();
+        self.inventory
+            .fmt_imp(&mut s, "price", |x| {
+                pricing.buy_price(x.cost(), self.inventory.n_available(x), reputation)
+            })
+            .unwrap();
         s.push('\n');
-        self.equipment_bag.fmt_imp(&mut s, "price").unwrap();
+        self.equipment_bag
+            .fmt_imp(&mut s, "price", |x| {
+                pricing.buy_price(
+                    x.cost(),
+                    self.equipment_bag.n_available(&x.kind),
+                    reputation,
+                )
+            })
+            .unwrap();
         s
     }
-    pub fn can_perform(&self, transaction: &Transaction) -> bool {
+    /// The player's items, with the gold the merchant offers for each.
+    pub fn offer_message(&self, player: &Player) -> String {
+        let pricing = self.catalogue.pricing;
+        let reputation = player.reputation;
+        let mut s = String::with_capacity(1 << 10);
+        player
+            .inventory
+            .fmt_imp(&mut s, "offer", |x| {
+                let price = pricing.sell_price(x.cost(), self.inventory.n_available(x), reputation);
+                player.skills.sale_price(price)
+            })
+            .unwrap();
+        s.push('\n');
+        player
+            .equipment_bag
+            .fmt_imp(&mut s, "offer", |x| {
+                let stock = self.equipment_bag.n_available(&x.kind);
+                player
+                    .skills
+                    .sale_price(pricing.sell_price(x.cost(), stock, reputation))
+            })
+            .unwrap();
+        writeln!(
+            s,
+            "{}: {} ({}% discount)",
+            "Reputation".bold().underline(),
+            reputation,
+            Pricing::discount(reputation)
+        )
+        .unwrap();
+        if !player.junk.is_empty() {
+            let junk: Vec<_> = player.junk.iter().map(Item::to_string).collect();
+            writeln!(s, "{}: {}", "Junk".bold().underline(), junk.join(", ")).unwrap();
+        }
+        s
+    }
+    pub fn can_perform(&self, transaction: &Transaction, price: usize) -> bool {
         match transaction {
+            Transaction::Sell { item, .. } => self.catalogue.deals_in(item) && self.gold >= price,
             Transaction::Buy {
                 item: Item::Consumable(x),
                 count,
@@ -128,21 +503,42 @@ impl Merchant {
             _ => true,
         }
     }
-    pub fn perform(&mut self, transaction: &Transaction) {
+    /// `gear` holds the pieces sold by the player, if any. Returns the gear
+    /// handed over to the player.
+    pub fn perform(
+        &mut self,
+        transaction: &Transaction,
+        price: usize,
+        gear: Vec<Piece>,
+    ) -> Vec<Piece> {
         match transaction {
-            Transaction::Buy { item, count } => match item {
-                Item::Consumable(x) => self.inventory.drop_multiple(*x, *count),
-                Item::Gear(x) => self.equipment_bag.drop_multiple(*x, *count),
-            },
-            Transaction::Sell { item, count } => match item {
-                Item::Consumable(x) => self.inventory.push_multiple(*x, *count),
-                Item::Gear(x) => self.equipment_bag.push_multiple(*x, *count),
-            },
-            Transaction::Quit => (),
+            Transaction::Buy { item, count } => {
+                self.gold += price;
+                match item {
+                    Item::Consumable(x) => self.inventory.drop_multiple(*x, *count),
+                    Item::Gear(x) => return self.equipment_bag.pop_multiple(*x, *count),
+                }
+            }
+            Transaction::Sell { item, count } => {
+                self.gold -= price;
+                match item {
+                    Item::Consumable(x) => self.inventory.push_multiple(*x, *count),
+                    Item::Gear(_) => gear.into_iter().for_each(|x| self.equipment_bag.push(x)),
+                }
+            }
+            _ => (),
         }
+        Vec::new()
     }
     pub fn describe_rejected_transaction(&self, transaction: &Transaction) {
         match transaction {
+            Transaction::Sell { item, .. } => {
+                if self.catalogue.deals_in(item) {
+                    println!("Merchant rejected transaction: insufficient gold!")
+                } else {
+                    println!("The {} does not deal in {}!", self.name(), item)
+                }
+            }
             Transaction::Buy { item, count } => match item {
                 Item::Consumable(x) => {
                     if self.inventory.n_available(x) < *count {
@@ -158,17 +554,31 @@ impl Merchant {
             _ => (),
         }
     }
-    pub fn menu(&self, gold: usize, player_msg: &str) -> Transaction {
+    pub fn menu(
+        &self,
+        gold: usize,
+        reputation: usize,
+        player_msg: &str,
+        ledger: &Ledger,
+    ) -> TradeCommand {
         let mut buf = String::with_capacity(1 << 7);
-        println!("---- Browsing merchant's wares... ----");
-        let msg = self.inventory_message();
+        println!("---- Browsing the {}'s wares... ----", self.name());
+        let msg = self.inventory_message(reputation);
         let n = msg.lines().count() + 2;
         println!("{}", msg);
-        let n = n + player_msg.lines().count() + 2;
+        let mut n = n + player_msg.lines().count() + 3;
         println!("---- Your items... ----");
         println!("{}", player_msg);
+        println!("e.g. `buy 2 hp mp`, `buy max food`, `sell all axe`, `sell junk`, `junk axe`, `price hp`; `q` to leave");
+        if !ledger.is_empty() {
+            let msg = ledger.to_string();
+            n += msg.lines().count() + 3;
+            println!("---- This visit... ----");
+            println!("{}", msg);
+            println!("`buyback 2 axe` to re-purchase what you sold, or `undo` the last trade");
+        }
         loop {
-            buf.clear();
+            String::clear(&mut buf);
 
             print!("(💰: {}) 🧞 ", gold);
             io::Write::flush(&mut io::stdout()).unwrap();
@@ -181,33 +591,411 @@ impl Merchant {
                 }
                 Err(e) => println!("Error in inventory menu readline: {:#?}", e),
             }
-            if let Ok(transaction) = buf.parse::<Transaction>() {
+            if let Ok(command) = buf.parse::<TradeCommand>() {
                 let _ = crate::readline::clear_last_n_lines(n);
-                break transaction;
+                break command;
             }
         }
     }
-    pub fn visit(&mut self, player: &mut Player) -> bool {
-        let transaction = self.menu(player.gold.clone(), &player.trade_msg());
-        match transaction {
-            Transaction::Quit => false,
-            _ => {
-                if self.can_perform(&transaction) {
-                    if player.can_perform(&transaction) {
-                        self.perform(&transaction);
-                        player.perform(&transaction);
-                    } else {
-                        player.describe_rejected_transaction(&transaction);
+    pub fn visit(&mut self, player: &mut Player, ledger: &mut Ledger) -> bool {
+        let command = self.menu(
+            player.gold,
+            player.reputation,
+            &self.offer_message(player),
+            ledger,
+        );
+        match command {
+            TradeCommand::Quit => return false,
+            TradeCommand::Undo => match ledger.0.pop() {
+                Some(receipt) => {
+                    if !self.undo(player, &receipt) {
+                        ledger.0.push(receipt);
                     }
+                }
+                None => println!("There is nothing to undo!"),
+            },
+            TradeCommand::Price(items) => print!("{}", self.price_message(&items, player)),
+            TradeCommand::Junk(item) => {
+                if player.junk.remove(&item) {
+                    println!("{item} is no longer marked as junk.");
                 } else {
-                    self.describe_rejected_transaction(&transaction);
+                    player.junk.insert(item);
+                    println!("{item} is marked as junk.");
                 }
+            }
+            TradeCommand::Trade { action, orders } => {
+                match self.resolve(action, &orders, player, ledger) {
+                    Ok(transactions) => {
+                        let declined =
+                            transactions.iter().any(|transaction| match transaction {
+                                Transaction::Buy {
+                                    item: Item::Gear(kind),
+                                    ..
+                                } => !self.equipment_bag.cheapest(*kind, 1).first().is_none_or(
+                                    |piece| confirm(&player.comparison_message(piece, false)),
+                                ),
+                                _ => false,
+                            });
+                        if !declined {
+                            self.exchange_all(player, ledger, &transactions);
+                        }
+                    }
+                    Err(msg) => println!("{msg}"),
+                }
+            }
+        }
+        true
+    }
+    /// Turn a command's orders into transactions, working out how many
+    /// units `all` and `max` stand for.
+    pub fn resolve(
+        &self,
+        action: TradeAction,
+        orders: &[(Quantity, Goods)],
+        player: &Player,
+        ledger: &Ledger,
+    ) -> Result<Vec<Transaction>, String> {
+        let mut transactions = Vec::with_capacity(orders.len());
+        let mut gold = player.gold;
+        for &(quantity, goods) in orders {
+            let item = match (action, goods) {
+                (TradeAction::Sell, Goods::Junk) => {
+                    let junk: Vec<_> = player
+                        .junk
+                        .iter()
+                        .filter(|x| self.catalogue.deals_in(x) && player.n_available(x) > 0)
+                        .map(|x| Transaction::Sell {
+                            item: *x,
+                            count: player.n_available(x),
+                        })
+                        .collect();
+                    if junk.is_empty() {
+                        return Err(format!("You have no junk which the {} buys!", self.name()));
+                    }
+                    transactions.extend(junk);
+                    continue;
+                }
+                (_, Goods::Junk) => return Err("Only junk can be sold!".to_string()),
+                (_, Goods::Item(item)) => item,
+            };
+            let count = match (action, quantity) {
+                (_, Quantity::Count(n)) => n,
+                (TradeAction::Sell, _) => player.n_available(&item),
+                (TradeAction::Buyback, _) => ledger.sold_units(item, usize::MAX).len(),
+                (_, Quantity::All) => self.stock(&item),
+                (_, Quantity::Max) => (1..=self.stock(&item))
+                    .take_while(|n| {
+                        self.quote(&Transaction::Buy { item, count: *n }, player) <= gold
+                    })
+                    .last()
+                    .unwrap_or(0),
+            };
+            if count == 0 {
+                return Err(format!("There is no {item} to trade!"));
+            }
+            let Some(transaction) = Transaction::new(action, item, count) else {
+                return Err(format!("{action:?} trades no goods!"));
+            };
+            if action == TradeAction::Buy {
+                gold = gold.saturating_sub(self.quote(&transaction, player));
+            }
+            transactions.push(transaction);
+        }
+        Ok(transactions)
+    }
+    /// Trade goods for gold, returning the receipt, or `None` if either side
+    /// rejects the transaction.
+    fn exchange(&mut self, player: &mut Player, transaction: &Transaction) -> Option<Receipt> {
+        let prices = self.unit_prices(transaction, player);
+        let price = prices.iter().sum();
+        if !self.can_perform(transaction, price) {
+            self.describe_rejected_transaction(transaction);
+            return None;
+        }
+        if !player.can_perform(transaction, price) {
+            player.describe_rejected_transaction(transaction);
+            return None;
+        }
+        let (action, item) = match *transaction {
+            Transaction::Sell { item, .. } => (TradeAction::Sell, item),
+            Transaction::Buy { item, .. } => (TradeAction::Buy, item),
+            _ => return None,
+        };
+        let sold = match (transaction, item) {
+            (Transaction::Sell { count, .. }, Item::Gear(kind)) => {
+                player.equipment_bag.cheapest(kind, *count)
+            }
+            _ => Vec::new(),
+        };
+        let bought = self.perform(transaction, price, sold.clone());
+        let gear = if action == TradeAction::Buy {
+            bought.clone()
+        } else {
+            sold
+        };
+        player.perform(transaction, price, bought);
+        Some(Receipt {
+            action,
+            item,
+            prices,
+            gear,
+        })
+    }
+    /// Apply every transaction, or none of them if any is rejected.
+    pub fn exchange_all(
+        &mut self,
+        player: &mut Player,
+        ledger: &mut Ledger,
+        transactions: &[Transaction],
+    ) -> bool {
+        let (mut merchant, mut buyer, mut book) = (self.clone(), player.clone(), ledger.clone());
+        let mut receipts = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            let ok = match *transaction {
+                Transaction::Buyback { item, count } => {
+                    merchant.buyback(&mut buyer, &mut book, item, count)
+                }
+                _ => match merchant.exchange(&mut buyer, transaction) {
+                    Some(receipt) => {
+                        book.0.push(receipt);
+                        true
+                    }
+                    None => false,
+                },
+            };
+            if !ok {
+                if transactions.len() > 1 {
+                    println!("Nothing was traded.");
+                }
+                return false;
+            }
+            receipts.extend(book.0.last().cloned());
+        }
+        for receipt in receipts {
+            println!("{receipt}.");
+        }
+        (*self, *player, *ledger) = (merchant, buyer, book);
+        true
+    }
+    pub fn price_message(&self, items: &[Item], player: &Player) -> String {
+        let mut s = String::with_capacity(1 << 8);
+        for item in items {
+            let buy = Transaction::Buy {
+                item: *item,
+                count: 1,
+            };
+            let sell = Transaction::Sell {
+                item: *item,
+                count: 1,
+            };
+            let buy = if self.stock(item) > 0 {
+                format!("buy for {} gold", self.quote(&buy, player))
+            } else {
+                "out of stock".to_string()
+            };
+            let sell = if self.catalogue.deals_in(item) {
+                format!("sell for {} gold", self.quote(&sell, player))
+            } else {
+                "not wanted".to_string()
+            };
+            writeln!(s, "{item}: {buy}, {sell}").unwrap();
+        }
+        s
+    }
+    /// Reverse a trade, returning `false` if either side no longer has
+    /// the gold or items to do so.
+    pub fn undo(&mut self, player: &mut Player, receipt: &Receipt) -> bool {
+        let price = receipt.price();
+        let count = receipt.count();
+        match receipt.action {
+            TradeAction::Buy => {
+                if !player.has_goods(receipt.item, count, &receipt.gear) {
+                    println!("You no longer have what you bought!");
+                    return false;
+                }
+                if self.gold < price {
+                    println!("Merchant rejected undo: insufficient gold!");
+                    return false;
+                }
+                player.remove_goods(receipt.item, count, &receipt.gear);
+                player.gold += price;
+                self.gold -= price;
+                self.add_goods(receipt.item, count, &receipt.gear);
+            }
+            _ => {
+                if player.gold < price {
+                    println!("Player rejected undo: insufficient gold!");
+                    return false;
+                }
+                if !self.remove_goods(receipt.item, count, &receipt.gear) {
+                    println!("The {} no longer has what you sold!", self.name());
+                    return false;
+                }
+                player.gold -= price;
+                self.gold += price;
+                player.add_goods(receipt.item, count, &receipt.gear);
+            }
+        }
+        player.reputation = player.reputation.saturating_sub(reputation_gain(price));
+        println!("Undid: {}", receipt);
+        true
+    }
+    /// Re-purchase up to `count` units of `item` sold during this visit,
+    /// newest first, at the price they fetched.
+    /// Returns `false` if the buyback is rejected.
+    pub fn buyback(
+        &mut self,
+        player: &mut Player,
+        ledger: &mut Ledger,
+        item: Item,
+        count: usize,
+    ) -> bool {
+        let units = ledger.sold_units(item, count);
+        if units.len() < count {
+            println!("You have not sold {count} {item} during this visit!");
+            return false;
+        }
+        let price: usize = units.iter().map(|(_, price, _)| price).sum();
+        let gear: Vec<Piece> = units.iter().filter_map(|(_, _, x)| x.clone()).collect();
+        if player.gold < price {
+            println!("Player rejected transaction: insufficient gold!");
+            return false;
+        }
+        if !self.remove_goods(item, count, &gear) {
+            println!("The {} no longer has what you sold!", self.name());
+            return false;
+        }
+        let reputation = ledger.forget_units(&units);
+        self.gold += price;
+        player.gold -= price;
+        player.add_goods(item, count, &gear);
+        player.reputation = player.reputation.saturating_sub(reputation);
+        let receipt = Receipt {
+            action: TradeAction::Buy,
+            item,
+            prices: units.iter().map(|(_, price, _)| *price).collect(),
+            gear,
+        };
+        ledger.0.push(receipt);
+        true
+    }
+    fn add_goods(&mut self, item: Item, count: usize, gear: &[Piece]) {
+        match item {
+            Item::Consumable(x) => self.inventory.push_multiple(x, count),
+            Item::Gear(_) => gear.iter().for_each(|x| self.equipment_bag.push(x.clone())),
+        }
+    }
+    fn remove_goods(&mut self, item: Item, count: usize, gear: &[Piece]) -> bool {
+        match item {
+            Item::Consumable(x) if self.inventory.n_available(&x) >= count => {
+                self.inventory.drop_multiple(x, count);
                 true
             }
+            Item::Consumable(_) => false,
+            Item::Gear(_) => self.equipment_bag.take(gear),
         }
     }
     pub fn trade(&mut self, player: &mut Player) {
-        while self.visit(player) {}
+        let mut ledger = Ledger::default();
+        while self.visit(player, &mut ledger) {}
+    }
+}
+
+/// Reputation earned by trading `price` gold.
+pub fn reputation_gain(price: usize) -> usize {
+    price / GOLD_PER_REPUTATION
+}
+
+/// A completed trade, as recorded in the ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    /// Either `Buy` or `Sell`
+    pub(crate) action: TradeAction,
+    pub(crate) item: Item,
+    /// The gold exchanged for each unit
+    pub(crate) prices: Vec<usize>,
+    /// The pieces which changed hands, if trading gear
+    pub(crate) gear: Vec<Piece>,
+}
+
+impl Receipt {
+    pub fn count(&self) -> usize {
+        self.prices.len()
+    }
+    pub fn price(&self) -> usize {
+        self.prices.iter().sum()
+    }
+}
+
+impl fmt::Display for Receipt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.action == TradeAction::Buy {
+            "Bought"
+        } else {
+            "Sold"
+        };
+        match self.count() {
+            1 => write!(f, "{verb} 1 {} for {} gold", self.item, self.price()),
+            n => write!(f, "{verb} {n} {}s for {} gold", self.item, self.price()),
+        }
+    }
+}
+
+/// The trades made during one visit to a merchant.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Ledger(pub(crate) Vec<Receipt>);
+
+impl Ledger {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Up to `count` units of `item` which were sold, newest first, as the
+    /// receipt's index, the price fetched and the piece of gear, if any.
+    pub(crate) fn sold_units(
+        &self,
+        item: Item,
+        count: usize,
+    ) -> Vec<(usize, usize, Option<Piece>)> {
+        self.0
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, x)| x.action == TradeAction::Sell && x.item == item)
+            .flat_map(|(i, x)| {
+                x.prices
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(move |(j, price)| (i, *price, x.gear.get(j).cloned()))
+            })
+            .take(count)
+            .collect()
+    }
+    /// Remove bought-back units from their receipts, so that they cannot be
+    /// bought back twice. Returns the reputation their sale earned.
+    pub(crate) fn forget_units(&mut self, units: &[(usize, usize, Option<Piece>)]) -> usize {
+        let earned =
+            |ledger: &Self| -> usize { ledger.0.iter().map(|x| reputation_gain(x.price())).sum() };
+        let before = earned(self);
+        for (i, _, piece) in units {
+            let receipt = &mut self.0[*i];
+            receipt.prices.pop();
+            if piece.is_some() {
+                receipt.gear.pop();
+            }
+        }
+        let lost = before - earned(self);
+        self.0.retain(|x| x.count() > 0);
+        lost
+    }
+}
+
+impl fmt::Display for Ledger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, receipt) in self.0.iter().enumerate() {
+            writeln!(f, "{:>3} {}", i + 1, receipt)?;
+        }
+        Ok(())
     }
 }
 
@@ -230,6 +1018,8 @@ mod tests {
             test_eq!(TradeAction::Buy ; "buy", "b", "BUY", "B", "bUY");
             test_eq!(TradeAction::Sell ; "sell", "s", "SELL", "S", "sElL");
             test_eq!(TradeAction::Quit ; "quit", "q", "QUIT", "Q", "Quit");
+            test_eq!(TradeAction::Buyback ; "buyback", "bb", "BuyBack");
+            test_eq!(TradeAction::Undo ; "undo", "u", "UNDO");
 
             macro_rules! test_err {
                 ($($s:literal),+) => {
@@ -241,19 +1031,172 @@ mod tests {
             test_err!("a", "c", "bu", "sel", "qui", "1234");
         }
     }
-    mod transaction {
+    #[test]
+    fn pricing() {
+        let pricing = Pricing::default();
+        assert_eq!(pricing.demand_pct(pricing.par), 100);
+        assert!(pricing.demand_pct(0) > pricing.demand_pct(10));
+        assert_eq!(pricing.demand_pct(1000), 50);
+        assert_eq!(pricing.buy_price(100, pricing.par, 0), 125);
+        assert_eq!(pricing.sell_price(100, pricing.par, 0), 50);
+        assert_eq!(
+            pricing.buy_price(100, pricing.par, MAX_DISCOUNT * REPUTATION_PER_DISCOUNT),
+            100
+        );
+        assert_eq!(Pricing::discount(usize::MAX), MAX_DISCOUNT);
+
+        let merchant = Merchant::new(crate::market::Shop::Apothecary.catalogue());
+        let player = Player::new(crate::class::Class::Warrior);
+        let buy = Transaction::new(TradeAction::Buy, Item::from(Elixir), 2).unwrap();
+        let one = Transaction::new(TradeAction::Buy, Item::from(Elixir), 1).unwrap();
+        assert!(merchant.quote(&buy, &player) > 2 * merchant.quote(&one, &player));
+        let sell = Transaction::new(TradeAction::Sell, Item::from(Food), 1).unwrap();
+        assert!(merchant.quote(&sell, &player) < Food.cost());
+    }
+
+    #[test]
+    fn restock() {
+        let mut rng = rand::thread_rng();
+        let mut merchant = Merchant::new(crate::market::Shop::Apothecary.catalogue());
+        merchant.inventory.drop_multiple(Elixir, 1);
+        merchant.gold = 0;
+        merchant.restock_imp(&mut rng, 1);
+        assert_eq!(merchant.inventory.n_available(&Elixir), 1);
+        assert_eq!(merchant.gold, Merchant::purse(1));
+        merchant.restock_imp(&mut rng, 11);
+        assert_eq!(merchant.inventory.n_available(&Elixir), 2);
+        assert_eq!(merchant.inventory.n_available(&Food), 40);
+        assert_eq!(merchant.gold, Merchant::purse(11));
+
+        let player = Player::new(crate::class::Class::Warrior);
+        let sell = Transaction::new(TradeAction::Sell, Item::from(Elixir), 1).unwrap();
+        merchant.gold = 0;
+        assert!(!merchant.can_perform(&sell, merchant.quote(&sell, &player)));
+    }
+
+    #[test]
+    fn ledger() {
+        let mut merchant = Merchant::new(crate::market::Shop::Apothecary.catalogue());
+        let mut player = Player::new(crate::class::Class::Warrior);
+        player.inventory.push_multiple(HealthPotion, 3);
+        let (gold, potions) = (player.gold, player.inventory.n_available(&HealthPotion));
+
+        let sell = Transaction::new(TradeAction::Sell, Item::from(HealthPotion), 3).unwrap();
+        let prices = merchant.unit_prices(&sell, &player);
+        let price = prices.iter().sum();
+        merchant.perform(&sell, price, Vec::new());
+        player.perform(&sell, price, Vec::new());
+        let mut ledger = Ledger(vec![Receipt {
+            action: TradeAction::Sell,
+            item: Item::from(HealthPotion),
+            prices: prices.clone(),
+            gear: Vec::new(),
+        }]);
+
+        merchant.buyback(&mut player, &mut ledger, Item::from(HealthPotion), 1);
+        assert_eq!(player.inventory.n_available(&HealthPotion), potions - 2);
+        assert_eq!(player.gold, gold + price - prices[2]);
+        assert_eq!(ledger.0[0].count(), 2);
+        assert_eq!(ledger.0[1].action, TradeAction::Buy);
+
+        let receipt = ledger.0.pop().unwrap();
+        assert!(merchant.undo(&mut player, &receipt));
+        let receipt = ledger.0.pop().unwrap();
+        assert!(merchant.undo(&mut player, &receipt));
+        // The bought-back potion left the sale, so it stays sold once undone
+        assert_eq!(player.inventory.n_available(&HealthPotion), potions - 1);
+        assert_eq!(player.gold, gold + prices[2]);
+        assert!(ledger.is_empty());
+    }
+
+    #[test]
+    fn buyback_reputation() {
+        let mut merchant = Merchant::new(crate::market::Shop::Apothecary.catalogue());
+        let mut player = Player::new(crate::class::Class::Warrior);
+        player.inventory.push_multiple(Elixir, 5);
+        assert_eq!(reputation_gain(GOLD_PER_REPUTATION - 1), 0);
+
+        let sell = Transaction::new(TradeAction::Sell, Item::from(Elixir), 5).unwrap();
+        let prices = merchant.unit_prices(&sell, &player);
+        let price = prices.iter().sum();
+        merchant.perform(&sell, price, Vec::new());
+        player.perform(&sell, price, Vec::new());
+        assert!(player.reputation > 0);
+        let mut ledger = Ledger(vec![Receipt {
+            action: TradeAction::Sell,
+            item: Item::from(Elixir),
+            prices,
+            gear: Vec::new(),
+        }]);
+        // Buying back one at a time takes back all the sale earned
+        for _ in 0..5 {
+            assert!(merchant.buyback(&mut player, &mut ledger, Item::from(Elixir), 1));
+        }
+        assert_eq!(player.reputation, 0);
+    }
+
+    mod trade_command {
         use super::*;
 
         #[test]
         fn from_str() {
+            assert_eq!(
+                "buy 2 hp mp max food".parse::<TradeCommand>().unwrap(),
+                TradeCommand::Trade {
+                    action: TradeAction::Buy,
+                    orders: vec![
+                        (Quantity::Count(2), Goods::Item(Item::from(HealthPotion))),
+                        (Quantity::Count(1), Goods::Item(Item::from(ManaPotion))),
+                        (Quantity::Max, Goods::Item(Item::from(Food))),
+                    ],
+                }
+            );
+            assert_eq!(
+                "sell all health potion junk"
+                    .parse::<TradeCommand>()
+                    .unwrap(),
+                TradeCommand::Trade {
+                    action: TradeAction::Sell,
+                    orders: vec![
+                        (Quantity::All, Goods::Item(Item::from(HealthPotion))),
+                        (Quantity::Count(1), Goods::Junk),
+                    ],
+                }
+            );
+            assert_eq!(
+                "price hp axe".parse::<TradeCommand>().unwrap(),
+                TradeCommand::Price(vec![
+                    Item::from(HealthPotion),
+                    Item::from(crate::equipment::Gear::Axe)
+                ])
+            );
+            assert_eq!(
+                "junk food".parse::<TradeCommand>().unwrap(),
+                TradeCommand::Junk(Item::from(Food))
+            );
+            assert_eq!("q".parse::<TradeCommand>().unwrap(), TradeCommand::Quit);
+            assert_eq!("u".parse::<TradeCommand>().unwrap(), TradeCommand::Undo);
+            assert!("buy".parse::<TradeCommand>().is_err());
+            assert!("buy 2".parse::<TradeCommand>().is_err());
+            assert!("price 2 hp".parse::<TradeCommand>().is_err());
+            assert!("sell hp nonsense".parse::<TradeCommand>().is_err());
+        }
+
+        #[test]
+        fn items() {
             macro_rules! test {
                 ($action:expr, $a:literal, $item:expr ; $($s:literal),+) => {
                     $(
-                        assert_eq!(format!("{} {}", $a, $s).parse::<Transaction>().unwrap(), Transaction::new($action, $item, 1));
-                        assert_eq!(format!("{} 1 {}", $a, $s).parse::<Transaction>().unwrap(), Transaction::new($action, $item, 1));
-                        assert_eq!(format!("{} 5 {}", $a, $s).parse::<Transaction>().unwrap(), Transaction::new($action, $item, 5));
+                        for (n, s) in [(1, format!("{} {}", $a, $s)), (1, format!("{} 1 {}", $a, $s)), (5, format!("{} 5 {}", $a, $s))] {
+                            assert_eq!(
+                                s.parse::<TradeCommand>().unwrap(),
+                                TradeCommand::Trade {
+                                    action: $action,
+                                    orders: vec![(Quantity::Count(n), Goods::Item($item))],
+                                }
+                            );
+                        }
                     )+
-
                 }
             }
             test!(TradeAction::Buy, "b", Item::from(HealthPotion) ; "hp", "HP", "health potion", "hEalth poTION");
@@ -262,6 +1205,42 @@ mod tests {
             test!(TradeAction::Sell, "s", Item::from(HealthPotion) ; "hp", "HP", "health potion", "hEalth poTION");
             test!(TradeAction::Sell, "s", Item::from(ManaPotion) ; "mp", "MP", "mana potion", "MaNA poTION");
             test!(TradeAction::Sell, "s", Item::from(Food) ; "f", "F", "food", "FOOd");
+            test!(TradeAction::Buyback, "bb", Item::from(Food) ; "f", "food");
+        }
+
+        #[test]
+        fn atomic() {
+            let mut merchant = Merchant::new(crate::market::Shop::Apothecary.catalogue());
+            let mut player = Player::new(crate::class::Class::Warrior);
+            let mut ledger = Ledger::default();
+            player.gold = 100;
+
+            let orders = [(Quantity::Max, Goods::Item(Item::from(Food)))];
+            let transactions = merchant
+                .resolve(TradeAction::Buy, &orders, &player, &ledger)
+                .unwrap();
+            assert!(merchant.quote(&transactions[0], &player) <= player.gold);
+            assert!(merchant.exchange_all(&mut player, &mut ledger, &transactions));
+
+            let (before, gold) = (merchant.clone(), player.gold);
+            player.junk.insert(Item::from(Food));
+            let orders = [
+                (Quantity::Count(1), Goods::Junk),
+                (Quantity::Count(1), Goods::Item(Item::from(Elixir))),
+            ];
+            let transactions = merchant
+                .resolve(TradeAction::Sell, &orders, &player, &ledger)
+                .unwrap();
+            assert_eq!(transactions.len(), 2);
+            assert!(!merchant.exchange_all(&mut player, &mut ledger, &transactions));
+            assert_eq!(merchant, before);
+            assert_eq!(player.gold, gold);
+            assert_eq!(ledger.0.len(), 1);
+
+            let orders = [(Quantity::Count(1), Goods::Item(Item::from(Food)))];
+            assert!(merchant
+                .resolve(TradeAction::Undo, &orders, &player, &ledger)
+                .is_err());
         }
     }
 }
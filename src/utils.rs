@@ -1,7 +1,68 @@
 use regex::Regex;
-use std::sync::LazyLock;
+use std::{
+    env, fs,
+    io::{self, BufRead},
+    path::PathBuf,
+    sync::LazyLock,
+};
+
+/// The directory holding the game's files, following the XDG base
+/// directory spec; created if missing.
+pub(crate) fn data_dir() -> io::Result<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME").filter(|x| !x.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => match env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".local").join("share"),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "neither XDG_DATA_HOME nor HOME is set",
+                ))
+            }
+        },
+    };
+    let dir = base.join("dungeoncrawl");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
 
 pub(crate) fn is_quit(s: &str) -> bool {
     static RE_QUIT: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:quit|q)$").unwrap());
     RE_QUIT.is_match(s)
 }
+
+pub(crate) fn is_yes(s: &str) -> bool {
+    static RE_YES: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:yes|y)$").unwrap());
+    RE_YES.is_match(s)
+}
+
+pub(crate) fn is_no(s: &str) -> bool {
+    static RE_NO: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?i)^(?:no|n)$").unwrap());
+    RE_NO.is_match(s)
+}
+
+/// Ask a yes-or-no question below `msg`, clearing both afterwards.
+pub(crate) fn confirm(msg: &str) -> bool {
+    let mut buf = String::with_capacity(1 << 7);
+    println!("{}", msg);
+    let n = msg.lines().count();
+    loop {
+        String::clear(&mut buf);
+        print!("(y/n) ");
+        io::Write::flush(&mut io::stdout()).unwrap();
+
+        let stdin = io::stdin();
+        let mut handle = stdin.lock();
+        match handle.read_line(&mut buf) {
+            Ok(_) => {
+                let _ = crate::readline::clear_last_n_lines(1);
+            }
+            Err(e) => println!("Error in confirmation readline: {:#?}", e),
+        }
+        let s = buf.trim();
+        if is_yes(s) || is_no(s) {
+            let _ = crate::readline::clear_last_n_lines(n);
+            return is_yes(s);
+        }
+    }
+}